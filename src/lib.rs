@@ -1,7 +1,27 @@
 pub mod lib {
+    pub mod arccache;
+    pub mod backedcache;
     pub mod basiccache;
     pub mod cache;
+    pub mod clock;
+    pub mod clockcache;
+    pub mod codec;
+    pub mod concurrentcache;
+    pub mod lfucache;
+    pub mod loggedcache;
     pub mod lrucache;
+    pub mod policy;
+    pub mod policycache;
+    pub mod s3fifocache;
+    pub mod sievecache;
+    pub mod sketch;
+    pub mod snapshot;
+    pub mod stats;
+    pub mod store;
+    pub mod tieredcache;
+    pub mod timerwheel;
+    pub mod tinylfucache;
+    pub mod weigher;
     pub mod linkedlist {
         pub mod list_array;
         pub mod list_raw;
@@ -0,0 +1,205 @@
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::lib::linkedlist::list_array::DoublyLinkedList;
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
+
+/// ARC (Adaptive Replacement Cache) 缓存
+pub type ArcCache<K, V> = PolicyCache<K, V, ArcPolicy<K>>;
+
+impl<K, V> ArcCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(max_size: usize) -> Self {
+        PolicyCache::with_policy(ArcPolicy::new(max_size), max_size)
+    }
+}
+
+/// ARC 策略
+///
+/// T1 保存只访问过一次的键，T2 保存至少访问过两次的键；
+/// B1、B2 分别记录最近从 T1、T2 淘汰的键（幽灵条目）。
+/// 幽灵命中会调整 T1 的目标大小 p，使缓存在偏重新近性和偏重频率之间自适应，
+/// 一次性的顺序扫描只会冲刷 T1，不会挤掉 T2 中的热点数据。
+pub struct ArcPolicy<K> {
+    index: HashMap<K, (Segment, usize)>,
+    t1: DoublyLinkedList<K>,
+    t2: DoublyLinkedList<K>,
+    b1: DoublyLinkedList<K>,
+    b2: DoublyLinkedList<K>,
+    // T1 的目标大小
+    p: usize,
+    capacity: usize,
+    // 正在插入的键命中了 B2，REPLACE 在 |T1| == p 时也从 T1 淘汰
+    incoming_b2_hit: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    T1,
+    T2,
+    B1,
+    B2,
+}
+
+impl<K> ArcPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    /// `capacity` 为缓存容量 c，幽灵链表最多再记录 c 个键
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            index: HashMap::new(),
+            t1: DoublyLinkedList::new(),
+            t2: DoublyLinkedList::new(),
+            b1: DoublyLinkedList::new(),
+            b2: DoublyLinkedList::new(),
+            p: 0,
+            capacity,
+            incoming_b2_hit: false,
+        }
+    }
+
+    fn list_mut(&mut self, segment: Segment) -> &mut DoublyLinkedList<K> {
+        match segment {
+            Segment::T1 => &mut self.t1,
+            Segment::T2 => &mut self.t2,
+            Segment::B1 => &mut self.b1,
+            Segment::B2 => &mut self.b2,
+        }
+    }
+
+    fn push(&mut self, key: &K, segment: Segment) {
+        let node = self.list_mut(segment).push_front(key.clone());
+        self.index.insert(key.clone(), (segment, node));
+    }
+
+    fn directory_len(&self) -> usize {
+        self.t1.len() + self.t2.len() + self.b1.len() + self.b2.len()
+    }
+
+    fn pop_ghost(&mut self, segment: Segment) {
+        if let Some(key) = self.list_mut(segment).pop_back() {
+            self.index.remove(&key);
+        }
+    }
+
+    /// 保证 |T1| + |B1| <= c 且目录总大小 <= 2c
+    fn trim_ghosts(&mut self) {
+        while self.t1.len() + self.b1.len() > self.capacity && !self.b1.is_empty() {
+            self.pop_ghost(Segment::B1);
+        }
+        while self.directory_len() > 2 * self.capacity {
+            if !self.b2.is_empty() {
+                self.pop_ghost(Segment::B2);
+            } else if !self.b1.is_empty() {
+                self.pop_ghost(Segment::B1);
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl<K> EvictionPolicy<K> for ArcPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    /// 幽灵命中时先调整 p，随后的 REPLACE 按调整后的 p 选择淘汰对象
+    fn before_insert(&mut self, key: &K) {
+        match self.index.get(key) {
+            // 幽灵命中 B1：说明 T1 太小，增大 p
+            Some((Segment::B1, _)) => {
+                let delta = (self.b2.len() / self.b1.len()).max(1);
+                self.p = (self.p + delta).min(self.capacity);
+            }
+            // 幽灵命中 B2：说明 T2 太小，减小 p
+            Some((Segment::B2, _)) => {
+                let delta = (self.b1.len() / self.b2.len()).max(1);
+                self.p = self.p.saturating_sub(delta);
+                self.incoming_b2_hit = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_insert(&mut self, key: &K) {
+        self.incoming_b2_hit = false;
+        let segment = match self.index.remove(key) {
+            Some((segment, node)) => {
+                self.list_mut(segment).remove(node);
+                Segment::T2
+            }
+            None => Segment::T1,
+        };
+        self.push(key, segment);
+        self.trim_ghosts();
+    }
+
+    /// 命中的键移到 T2 头部
+    fn on_access(&mut self, key: &K) {
+        match self.index.get(key) {
+            Some(&(Segment::T2, node)) => self.t2.move_to_front(node),
+            Some(&(Segment::T1, node)) => {
+                self.t1.remove(node);
+                self.push(key, Segment::T2);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(&(segment, node)) = self.index.get(key)
+            && matches!(segment, Segment::T1 | Segment::T2)
+        {
+            self.index.remove(key);
+            self.list_mut(segment).remove(node);
+        }
+    }
+
+    /// ARC 的 REPLACE：从 T1 或 T2 淘汰一个键，并把它记入对应的幽灵链表
+    fn choose_victim(&mut self) -> Option<K> {
+        let t1_len = self.t1.len();
+        let from_t1 = t1_len > 0
+            && (t1_len > self.p || (self.incoming_b2_hit && t1_len == self.p) || self.t2.is_empty());
+        let (from, ghost) = if from_t1 {
+            (Segment::T1, Segment::B1)
+        } else {
+            (Segment::T2, Segment::B2)
+        };
+        let key = self.list_mut(from).pop_back()?;
+        self.push(&key, ghost);
+        Some(key)
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.t1.clear();
+        self.t2.clear();
+        self.b1.clear();
+        self.b2.clear();
+        self.p = 0;
+        self.incoming_b2_hit = false;
+    }
+
+    /// 之后没有访问时 REPLACE 依次淘汰的顺序，p 保持不变
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        let mut t1: VecDeque<&K> = self.t1.iter().collect();
+        let mut t2: VecDeque<&K> = self.t2.iter().collect();
+        let mut keys = Vec::with_capacity(t1.len() + t2.len());
+        loop {
+            let from_t1 = !t1.is_empty()
+                && (t1.len() > self.p || (self.incoming_b2_hit && t1.len() == self.p) || t2.is_empty());
+            let key = if from_t1 { t1.pop_back() } else { t2.pop_back() };
+            match key {
+                Some(key) => keys.push(key.clone()),
+                None => break,
+            }
+        }
+        Some(keys)
+    }
+}
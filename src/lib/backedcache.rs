@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::lib::cache::{Cache, CacheError, EntryExpiry, RemovalListener};
+use crate::lib::concurrentcache::lock;
+use crate::lib::stats::CacheStats;
+use crate::lib::store::{BackingStore, StoreError};
+
+/// 写入持久存储的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    /// 写穿：`insert` 和 `remove` 同步写入存储，存储写入成功后才更新缓存
+    WriteThrough,
+    /// 写回：只记录脏条目，由后台线程每隔 `flush_interval` 或攒够 `max_batch` 个时批量写入
+    WriteBehind { flush_interval: Duration, max_batch: usize },
+}
+
+/// 带持久存储的缓存
+///
+/// 未命中时从存储加载并放入缓存；写入按 [`WriteMode`] 同步到存储。
+/// `peek`、`contains_key` 只查看缓存本身，`clear` 和过期清理也不会删除存储中的数据。
+pub struct BackedCache<K, V> {
+    cache: Box<dyn Cache<K, V>>,
+    store: Arc<dyn BackingStore<K, V>>,
+    // 写回模式下的脏条目和后台线程
+    dirty: Option<Arc<Dirty<K, V>>>,
+    flusher: Option<JoinHandle<()>>,
+}
+
+/// 尚未写入存储的修改，None 表示删除
+struct Dirty<K, V> {
+    pending: Mutex<Pending<K, V>>,
+    wake: Condvar,
+    // 保证同一时间只有一批修改在写入，避免旧值覆盖新值
+    flushing: Mutex<()>,
+    max_batch: usize,
+}
+
+struct Pending<K, V> {
+    writes: HashMap<K, Option<V>>,
+    // 正在写入存储的一批修改，写完之前读取仍以它为准
+    in_flight: HashMap<K, Option<V>>,
+    shutdown: bool,
+}
+
+impl<K, V> BackedCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    pub fn new(cache: Box<dyn Cache<K, V>>, store: Arc<dyn BackingStore<K, V>>, mode: WriteMode) -> Self {
+        let (dirty, flusher) = match mode {
+            WriteMode::WriteThrough => (None, None),
+            WriteMode::WriteBehind { flush_interval, max_batch } => {
+                let dirty = Arc::new(Dirty {
+                    pending: Mutex::new(Pending {
+                        writes: HashMap::new(),
+                        in_flight: HashMap::new(),
+                        shutdown: false,
+                    }),
+                    wake: Condvar::new(),
+                    flushing: Mutex::new(()),
+                    max_batch: max_batch.max(1),
+                });
+                let flusher = {
+                    let dirty = Arc::clone(&dirty);
+                    let store = Arc::clone(&store);
+                    thread::spawn(move || dirty.run(&*store, flush_interval))
+                };
+                (Some(dirty), Some(flusher))
+            }
+        };
+        Self {
+            cache,
+            store,
+            dirty,
+            flusher,
+        }
+    }
+}
+
+impl<K, V> BackedCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// 与 `get` 相同，但返回从存储加载时的错误
+    pub fn try_get(&mut self, key: &K) -> Result<Option<V>, CacheError> {
+        if let Some(value) = self.cache.get(key) {
+            return Ok(Some(value));
+        }
+        // 写回模式下还没写入存储的修改比存储里的值新
+        if let Some(dirty) = &self.dirty
+            && let Some(write) = dirty.lookup(key)
+        {
+            if let Some(value) = &write {
+                self.cache.insert(key.clone(), value.clone());
+            }
+            return Ok(write);
+        }
+        let start = Instant::now();
+        let result = self.store.load(key);
+        self.cache.record_load(result.is_ok(), start.elapsed());
+        let value = result?;
+        if let Some(value) = &value {
+            self.cache.insert(key.clone(), value.clone());
+        }
+        Ok(value)
+    }
+
+    /// 与 `remove` 相同，但返回写入存储时的错误；写穿模式下存储删除失败时缓存保持不变
+    pub fn try_remove(&mut self, key: &K) -> Result<Option<V>, CacheError> {
+        match &self.dirty {
+            None => self.store.delete(key)?,
+            Some(dirty) => dirty.push(key.clone(), None),
+        }
+        Ok(self.cache.remove(key))
+    }
+
+    /// 立即把写回模式下积累的修改写入存储，写穿模式下什么也不做
+    pub fn flush(&self) -> Result<(), CacheError> {
+        match &self.dirty {
+            Some(dirty) => Ok(dirty.flush(&*self.store)?),
+            None => Ok(()),
+        }
+    }
+
+    /// 还没写入存储的修改数量
+    pub fn pending_writes(&self) -> usize {
+        self.dirty.as_ref().map_or(0, |dirty| dirty.len())
+    }
+}
+
+impl<K, V> Cache<K, V> for BackedCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    fn insert(&mut self, key: K, value: V) {
+        let _ = self.try_insert(key, value);
+    }
+
+    fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) {
+        let _ = self.try_insert_with_ttl(key, value, ttl);
+    }
+
+    fn insert_with_expiry(&mut self, key: K, value: V, ttl: Option<Duration>, tti: Option<Duration>) {
+        let _ = self.try_insert_with_expiry(key, value, ttl, tti);
+    }
+
+    fn try_insert(&mut self, key: K, value: V) -> Result<(), CacheError> {
+        self.persist(&key, &value)?;
+        let result = self.cache.try_insert(key.clone(), value);
+        self.invalidate_on_error(&key, result)
+    }
+
+    fn try_insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Result<(), CacheError> {
+        self.persist(&key, &value)?;
+        let result = self.cache.try_insert_with_ttl(key.clone(), value, ttl);
+        self.invalidate_on_error(&key, result)
+    }
+
+    fn try_insert_with_expiry(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+    ) -> Result<(), CacheError> {
+        self.persist(&key, &value)?;
+        let result = self.cache.try_insert_with_expiry(key.clone(), value, ttl, tti);
+        self.invalidate_on_error(&key, result)
+    }
+
+    /// 缓存未命中时从存储加载，加载失败按未命中处理
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.try_get(key).ok().flatten()
+    }
+
+    fn peek(&self, key: &K) -> Option<V> {
+        self.cache.peek(key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.cache.contains_key(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.try_remove(key).ok().flatten()
+    }
+
+    fn purge_expired(&mut self) -> usize {
+        self.cache.purge_expired()
+    }
+
+    fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    fn for_each_entry(&self, visit: &mut dyn FnMut(&K, &V, EntryExpiry)) {
+        self.cache.for_each_entry(visit);
+    }
+
+    fn set_removal_listener(&mut self, listener: RemovalListener<K, V>) {
+        self.cache.set_removal_listener(listener);
+    }
+
+    /// 统计来自内部缓存，从存储加载计入加载次数和耗时
+    fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    fn set_stats_enabled(&mut self, enabled: bool) {
+        self.cache.set_stats_enabled(enabled);
+    }
+
+    fn reset_stats(&mut self) {
+        self.cache.reset_stats();
+    }
+
+    fn record_load(&mut self, success: bool, elapsed: Duration) {
+        self.cache.record_load(success, elapsed);
+    }
+
+    fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+impl<K, V> BackedCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// 写穿模式下同步写入存储，写回模式下记为脏条目
+    fn persist(&self, key: &K, value: &V) -> Result<(), StoreError> {
+        match &self.dirty {
+            None => self.store.store(key, value),
+            Some(dirty) => {
+                dirty.push(key.clone(), Some(value.clone()));
+                Ok(())
+            }
+        }
+    }
+
+    /// 新值已经交给存储，缓存拒绝写入时删除其中的旧值，之后的读取回到存储
+    fn invalidate_on_error(&mut self, key: &K, result: Result<(), CacheError>) -> Result<(), CacheError> {
+        if result.is_err() {
+            self.cache.remove(key);
+        }
+        result
+    }
+}
+
+impl<K, V> Drop for BackedCache<K, V> {
+    /// 通知后台线程写完剩余的修改后退出
+    fn drop(&mut self) {
+        if let Some(dirty) = &self.dirty {
+            lock(&dirty.pending).shutdown = true;
+            dirty.wake.notify_all();
+        }
+        if let Some(flusher) = self.flusher.take() {
+            let _ = flusher.join();
+        }
+    }
+}
+
+impl<K, V> Dirty<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    fn push(&self, key: K, write: Option<V>) {
+        let mut pending = lock(&self.pending);
+        pending.writes.insert(key, write);
+        if pending.writes.len() >= self.max_batch {
+            self.wake.notify_all();
+        }
+    }
+
+    /// 键上还没写入存储的修改，先看新的脏条目，再看正在写入的一批
+    fn lookup(&self, key: &K) -> Option<Option<V>> {
+        let pending = lock(&self.pending);
+        pending.writes.get(key).or_else(|| pending.in_flight.get(key)).cloned()
+    }
+
+    /// 还没写入存储的键数
+    fn len(&self) -> usize {
+        let pending = lock(&self.pending);
+        let in_flight = pending.in_flight.keys().filter(|key| !pending.writes.contains_key(key)).count();
+        pending.writes.len() + in_flight
+    }
+
+    /// 写入当前所有脏条目；失败的条目放回去等下次重试，除非期间又有了更新的修改
+    ///
+    /// 每个条目写入存储成功后才从正在写入的批次中移除，在此之前读取不会越过它读到存储里的旧值。
+    fn flush(&self, store: &dyn BackingStore<K, V>) -> Result<(), StoreError> {
+        let _flushing = lock(&self.flushing);
+        let batch: Vec<(K, Option<V>)> = {
+            let mut pending = lock(&self.pending);
+            pending.in_flight = mem::take(&mut pending.writes);
+            pending.in_flight.iter().map(|(key, write)| (key.clone(), write.clone())).collect()
+        };
+        let mut first_error = None;
+        for (key, write) in batch {
+            let result = match &write {
+                Some(value) => store.store(&key, value),
+                None => store.delete(&key),
+            };
+            let mut pending = lock(&self.pending);
+            pending.in_flight.remove(&key);
+            if let Err(error) = result {
+                pending.writes.entry(key).or_insert(write);
+                first_error.get_or_insert(error);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// 后台线程：定时或攒够一批时写入，收到退出通知后最后写一次
+    fn run(&self, store: &dyn BackingStore<K, V>, flush_interval: Duration) {
+        let mut failed = false;
+        loop {
+            let shutdown = {
+                let mut pending = lock(&self.pending);
+                // 上次写入失败时至少等一个周期再重试，不在失败的批次上空转
+                if !pending.shutdown && (failed || pending.writes.len() < self.max_batch) {
+                    pending = self
+                        .wake
+                        .wait_timeout(pending, flush_interval)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .0;
+                }
+                pending.shutdown
+            };
+            failed = self.flush(store).is_err();
+            if shutdown {
+                break;
+            }
+        }
+    }
+}
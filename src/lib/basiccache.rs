@@ -1,70 +1,28 @@
 
-use std::collections::HashMap;
-use std::time::{SystemTime, Duration};
 use std::hash::Hash;
 
-use crate::lib::cache::{Cache, CacheEntry};
+use crate::lib::policy::NoEviction;
+use crate::lib::policycache::PolicyCache;
 
-pub struct BasicCache<K, V> {
-    data: HashMap<K, CacheEntry<V>>,
-    default_ttl: Option<Duration>,
-}
+/// 基础缓存：不限容量，不淘汰
+pub type BasicCache<K, V> = PolicyCache<K, V, NoEviction>;
 
 impl<K, V> BasicCache<K, V>
 where
-    K: Hash + Eq,
+    K: Hash + Eq + Clone,
     V: Clone,
 {
     pub fn new() -> Self {
-        Self {
-            data: HashMap::new(),
-            default_ttl: None,
-        }
+        PolicyCache::unbounded(NoEviction)
     }
 }
 
-impl<K, V> Cache<K, V> for BasicCache<K, V>
+impl<K, V> Default for BasicCache<K, V>
 where
-    K: Hash + Eq,
+    K: Hash + Eq + Clone,
     V: Clone,
 {
-    fn insert(&mut self, key: K, value: V) {
-        self.insert_with_ttl(key, value, self.default_ttl);
-    }
-
-    fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) {
-        let expiry = ttl.map(|duration| SystemTime::now() + duration);
-        self.data.insert(key, CacheEntry { value, expiry });
-    }
-
-    fn get(&self, key: &K) -> Option<V> {
-        match self.data.get(key) {
-            Some(entry) => {
-                // 检查是否过期
-                if let Some(expiry) = entry.expiry {
-                    if SystemTime::now() > expiry {
-                        return None; // 已过期
-                    }
-                }
-                Some(entry.value.clone())
-            }
-            None => None,
-        }
-    }
-
-    fn remove(&mut self, key: &K) -> Option<V> {
-        self.data.remove(key).map(|entry| entry.value)
-    }
-
-    fn clear(&mut self) {
-        self.data.clear();
-    }
-
-    fn len(&self) -> usize {
-        self.data.len()
-    }
-
-    fn is_empty(&self) -> bool {
-        self.data.is_empty()
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -1,7 +1,12 @@
-use crate::lib::basiccache::BasicCache;
-use crate::lib::lrucache::LruCache;
+use crate::lib::clock::{Clock, MonotonicClock};
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
+use crate::lib::stats::CacheStats;
+use crate::lib::store::StoreError;
+use std::fmt;
 use std::hash::Hash;
-use std::time::{Duration, SystemTime};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// 缓存类型枚举，用于指定不同的缓存实现
 #[derive(Debug, Clone)]
@@ -10,6 +15,80 @@ pub enum CacheType {
     Basic,
     /// LRU (Least Recently Used) 实现
     Lru(usize), // usize 表示最大容量
+    /// LFU (Least Frequently Used) 实现，同频次按最近使用淘汰
+    Lfu(usize), // usize 表示最大容量
+    /// ARC (Adaptive Replacement Cache) 实现，抗顺序扫描
+    Arc(usize), // usize 表示最大容量
+    /// W-TinyLFU 实现：窗口 LRU + 分段 LRU，由频次估计决定准入
+    TinyLfu(usize), // usize 表示最大容量
+    /// CLOCK (二次机会) 实现，命中不移动条目
+    Clock(usize), // usize 表示最大容量
+    /// SIEVE 实现，命中不移动条目
+    Sieve(usize), // usize 表示最大容量
+    /// S3-FIFO 实现：小队列 + 主队列 + 幽灵队列
+    S3Fifo(usize), // usize 表示最大容量
+}
+
+/// 缓存操作的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheError {
+    /// 单个条目的权重超过了缓存的权重上限，写入被拒绝；`insert` 系列覆盖写入时原来的条目已按替换删除
+    EntryTooHeavy { weight: u64, max_weight: u64 },
+    /// 超出容量且策略没有可淘汰的条目，新值没有写入；覆盖写入时原来的条目也已删除
+    CacheFull,
+    /// 背后的持久存储读写失败
+    Store(StoreError),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::EntryTooHeavy { weight, max_weight } => {
+                write!(f, "条目权重 {} 超过缓存权重上限 {}", weight, max_weight)
+            }
+            CacheError::CacheFull => write!(f, "缓存已满且没有可淘汰的条目"),
+            CacheError::Store(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+impl From<StoreError> for CacheError {
+    fn from(error: StoreError) -> Self {
+        CacheError::Store(error)
+    }
+}
+
+/// 条目离开缓存的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RemovalCause {
+    /// 调用 `remove` 删除
+    Explicit,
+    /// 被同一个键的新值覆盖
+    Replaced,
+    /// TTL 或空闲时间到期
+    Expired,
+    /// 超出容量被淘汰
+    Evicted,
+    /// 调用 `clear` 清空
+    Cleared,
+}
+
+/// 删除监听器，条目离开缓存时以键、值和原因调用
+///
+/// 在缓存的方法内部同步调用，不能再访问同一个缓存。
+pub type RemovalListener<K, V> = Arc<dyn Fn(&K, &V, RemovalCause) + Send + Sync>;
+
+/// 条目在某一时刻的过期信息，用于保存和恢复快照
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntryExpiry {
+    /// 距离 TTL 到期的剩余时间
+    pub ttl_remaining: Option<Duration>,
+    /// 空闲时间（TTI）设置
+    pub idle: Option<Duration>,
+    /// 距离空闲到期的剩余时间
+    pub idle_remaining: Option<Duration>,
 }
 
 /// 缓存 trait，定义缓存的基本操作
@@ -20,21 +99,250 @@ where
 {
     fn insert(&mut self, key: K, value: V);
     fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>);
-    fn get(&self, key: &K) -> Option<V>;
+    /// 同时指定存活时间 `ttl` 和空闲时间 `tti`，哪个先到期就按哪个过期
+    ///
+    /// 空闲时间从最后一次写入或 `get` 读取开始计算。
+    fn insert_with_expiry(&mut self, key: K, value: V, ttl: Option<Duration>, tti: Option<Duration>);
+    /// 与 `insert` 相同，但写入被拒绝时返回错误，缓存保持不变
+    ///
+    /// `insert` 系列方法遇到同样的情况会静默忽略这次写入。
+    fn try_insert(&mut self, key: K, value: V) -> Result<(), CacheError>;
+    fn try_insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Result<(), CacheError>;
+    fn try_insert_with_expiry(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+    ) -> Result<(), CacheError>;
+    /// 读取值，并按策略记录这次访问（例如 LRU 会把该键提升为最近使用）
+    fn get(&mut self, key: &K) -> Option<V>;
+    /// 读取值但不影响淘汰顺序
+    fn peek(&self, key: &K) -> Option<V>;
+    /// 键是否存在且未过期，不影响淘汰顺序
+    fn contains_key(&self, key: &K) -> bool;
     fn remove(&mut self, key: &K) -> Option<V>;
+    /// 删除所有已过期的条目，返回删除的数量
+    fn purge_expired(&mut self) -> usize;
     fn clear(&mut self);
+    /// 访问所有未过期的条目，先访问最先被淘汰的；策略不提供顺序时顺序不定
+    ///
+    /// 策略给出的是之后没有访问时的淘汰顺序。按访问顺序依次写入另一个 LRU 缓存可以重建相同的顺序，
+    /// 其他策略的访问位和频次不会随之保留。不影响淘汰顺序和统计。
+    fn for_each_entry(&self, visit: &mut dyn FnMut(&K, &V, EntryExpiry));
+    /// 设置删除监听器，替换之前设置的监听器
+    fn set_removal_listener(&mut self, listener: RemovalListener<K, V>);
+    /// 当前的统计快照，未开启统计时全为零
+    fn stats(&self) -> CacheStats;
+    /// 开启或关闭统计，关闭时丢弃已有的计数
+    fn set_stats_enabled(&mut self, enabled: bool);
+    /// 把统计计数清零
+    fn reset_stats(&mut self);
+    /// 记录一次加载的结果和耗时，由读穿透加载调用；默认不记录
+    fn record_load(&mut self, _success: bool, _elapsed: Duration) {}
+    /// 未过期的条目数量
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool;
 }
 
+/// 缓存的扩展方法，对所有实现了 [`Cache`] 的类型（包括 `Box<dyn Cache>`）自动可用
+pub trait CacheExt<K, V>: Cache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    /// 读取值，未命中时调用 `load` 计算并按默认 TTL 写入
+    fn get_or_insert_with<F>(&mut self, key: K, load: F) -> V
+    where
+        F: FnOnce() -> V,
+    {
+        match self.get(&key) {
+            Some(value) => value,
+            None => {
+                let start = Instant::now();
+                let value = load();
+                self.record_load(true, start.elapsed());
+                self.insert(key, value.clone());
+                value
+            }
+        }
+    }
+
+    /// 与 `get_or_insert_with` 相同，加载的值使用指定的 TTL
+    fn get_or_insert_with_ttl<F>(&mut self, key: K, ttl: Option<Duration>, load: F) -> V
+    where
+        F: FnOnce() -> V,
+    {
+        match self.get(&key) {
+            Some(value) => value,
+            None => {
+                let start = Instant::now();
+                let value = load();
+                self.record_load(true, start.elapsed());
+                self.insert_with_ttl(key, value.clone(), ttl);
+                value
+            }
+        }
+    }
+
+    /// 读取值，未命中时调用可能失败的 `load`；加载失败时不写入任何内容，直接返回错误
+    fn try_get_or_insert_with<F, E>(&mut self, key: K, load: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+    {
+        match self.get(&key) {
+            Some(value) => Ok(value),
+            None => {
+                let start = Instant::now();
+                let result = load();
+                self.record_load(result.is_ok(), start.elapsed());
+                let value = result?;
+                self.insert(key, value.clone());
+                Ok(value)
+            }
+        }
+    }
+
+    /// 与 `try_get_or_insert_with` 相同，加载的值使用指定的 TTL
+    fn try_get_or_insert_with_ttl<F, E>(&mut self, key: K, ttl: Option<Duration>, load: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+    {
+        match self.get(&key) {
+            Some(value) => Ok(value),
+            None => {
+                let start = Instant::now();
+                let result = load();
+                self.record_load(result.is_ok(), start.elapsed());
+                let value = result?;
+                self.insert_with_ttl(key, value.clone(), ttl);
+                Ok(value)
+            }
+        }
+    }
+
+    /// 所有未过期条目的副本，按策略顺序从最近使用到最久未使用排列；策略不提供顺序时顺序不定
+    ///
+    /// 返回的是调用时的快照，之后修改缓存不影响它。不影响淘汰顺序和统计。
+    fn iter(&self) -> std::vec::IntoIter<(K, V)>
+    where
+        K: Clone,
+    {
+        let mut entries = Vec::with_capacity(self.len());
+        self.for_each_entry(&mut |key, value, _| entries.push((key.clone(), value.clone())));
+        // for_each_entry 先访问最先被淘汰的
+        entries.reverse();
+        entries.into_iter()
+    }
+
+    /// 按 [`iter`](Self::iter) 的顺序列出所有未过期的键
+    fn keys(&self) -> std::vec::IntoIter<K>
+    where
+        K: Clone,
+    {
+        let mut keys = Vec::with_capacity(self.len());
+        self.for_each_entry(&mut |key, _, _| keys.push(key.clone()));
+        keys.reverse();
+        keys.into_iter()
+    }
+
+    /// 按 [`iter`](Self::iter) 的顺序列出所有未过期的值
+    fn values(&self) -> std::vec::IntoIter<V> {
+        let mut values = Vec::with_capacity(self.len());
+        self.for_each_entry(&mut |_, value, _| values.push(value.clone()));
+        values.reverse();
+        values.into_iter()
+    }
+
+    /// 只保留 `keep` 返回 true 的条目，其余按显式删除处理，返回删除的数量
+    fn retain<F>(&mut self, mut keep: F) -> usize
+    where
+        K: Clone,
+        F: FnMut(&K, &V) -> bool,
+    {
+        let mut doomed = Vec::new();
+        self.for_each_entry(&mut |key, value, _| {
+            if !keep(key, value) {
+                doomed.push(key.clone());
+            }
+        });
+        doomed.iter().filter(|key| self.remove(key).is_some()).count()
+    }
+
+    /// 取出所有未过期的条目（按 [`iter`](Self::iter) 的顺序）并清空缓存
+    ///
+    /// 取出的条目按显式删除通知监听器，剩下的过期条目按过期清理。
+    fn drain(&mut self) -> std::vec::IntoIter<(K, V)>
+    where
+        K: Clone,
+    {
+        let entries: Vec<(K, V)> = self.iter().collect();
+        for (key, _) in &entries {
+            self.remove(key);
+        }
+        self.purge_expired();
+        entries.into_iter()
+    }
+}
+
+impl<K, V, C> CacheExt<K, V> for C
+where
+    K: Hash + Eq,
+    V: Clone,
+    C: Cache<K, V> + ?Sized,
+{
+}
+
+/// 按缓存类型创建缓存并装箱，装成哪种 trait 对象由调用处的返回类型决定
+macro_rules! boxed_cache {
+    ($cache_type:expr, $clock:expr) => {{
+        use $crate::lib::{
+            arccache::ArcCache, basiccache::BasicCache, cache::CacheType, clockcache::ClockCache,
+            lfucache::LfuCache, lrucache::LruCache, s3fifocache::S3FifoCache, sievecache::SieveCache,
+            tinylfucache::TinyLfuCache,
+        };
+        match $cache_type {
+            CacheType::Basic => Box::new(BasicCache::new().with_clock($clock)),
+            CacheType::Lru(max_size) => Box::new(LruCache::new(max_size).with_clock($clock)),
+            CacheType::Lfu(max_size) => Box::new(LfuCache::new(max_size).with_clock($clock)),
+            CacheType::Arc(max_size) => Box::new(ArcCache::new(max_size).with_clock($clock)),
+            CacheType::TinyLfu(max_size) => Box::new(TinyLfuCache::new(max_size).with_clock($clock)),
+            CacheType::Clock(max_size) => Box::new(ClockCache::new(max_size).with_clock($clock)),
+            CacheType::Sieve(max_size) => Box::new(SieveCache::new(max_size).with_clock($clock)),
+            CacheType::S3Fifo(max_size) => Box::new(S3FifoCache::new(max_size).with_clock($clock)),
+        }
+    }};
+}
+pub(crate) use boxed_cache;
+
 pub fn new_cache<K, V>(cache_type: CacheType) -> Box<dyn Cache<K, V>>
 where
     K: Hash + Eq + Clone + 'static,
     V: Clone + 'static,
 {
-    match cache_type {
-        CacheType::Basic => Box::new(BasicCache::new()),
-        CacheType::Lru(max_size) => Box::new(LruCache::new(max_size)),
+    new_cache_with_clock(cache_type, MonotonicClock)
+}
+
+/// 使用指定时钟创建缓存，测试中可以传入 `MockClock` 手动推进时间
+pub fn new_cache_with_clock<K, V, C>(cache_type: CacheType, clock: C) -> Box<dyn Cache<K, V>>
+where
+    K: Hash + Eq + Clone + 'static,
+    V: Clone + 'static,
+    C: Clock + 'static,
+{
+    boxed_cache!(cache_type, clock)
+}
+
+/// 用任意淘汰策略（包括自定义策略）创建缓存，`max_size` 为 None 时不限容量
+pub fn new_cache_with_policy<K, V, P>(policy: P, max_size: Option<usize>) -> Box<dyn Cache<K, V>>
+where
+    K: Hash + Eq + Clone + 'static,
+    V: Clone + 'static,
+    P: EvictionPolicy<K> + 'static,
+{
+    match max_size {
+        Some(max_size) => Box::new(PolicyCache::with_policy(policy, max_size)),
+        None => Box::new(PolicyCache::unbounded(policy)),
     }
 }
 
@@ -42,5 +350,36 @@ where
 #[derive(Clone)]
 pub(crate) struct CacheEntry<T> {
     pub(crate) value: T,
-    pub(crate) expiry: Option<SystemTime>,
+    // 绝对过期时间（TTL）
+    pub(crate) expiry: Option<Instant>,
+    // 空闲时间（TTI），超过这么久没有访问就过期
+    pub(crate) idle: Option<Duration>,
+    pub(crate) last_access: Instant,
+    pub(crate) weight: u64,
+    // 插入时附加的标签，用于按标签批量失效
+    pub(crate) tags: Vec<String>,
+}
+
+impl<T> CacheEntry<T> {
+    /// TTL 和 TTI 中较早的过期时间
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        let idle_deadline = self.idle.map(|idle| self.last_access + idle);
+        match (self.expiry, idle_deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    pub(crate) fn expiry_at(&self, now: Instant) -> EntryExpiry {
+        EntryExpiry {
+            ttl_remaining: self.expiry.map(|expiry| expiry.saturating_duration_since(now)),
+            idle: self.idle,
+            idle_remaining: self.idle.map(|idle| (self.last_access + idle).saturating_duration_since(now)),
+        }
+    }
+
+    /// 在 `now` 时刻是否已过期
+    pub(crate) fn is_expired(&self, now: Instant) -> bool {
+        self.deadline().is_some_and(|deadline| now > deadline)
+    }
 }
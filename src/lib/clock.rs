@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 时间来源，缓存通过它判断条目是否过期
+///
+/// 注意与 CLOCK 淘汰算法（[`ClockCache`](crate::lib::clockcache::ClockCache)）无关。
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// 默认时钟，基于单调递增的 `Instant`，不受系统时间调整影响
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonotonicClock;
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// 手动推进的时钟，用于测试
+///
+/// 克隆出的时钟共享同一个时间，把一份交给缓存、另一份留在测试里推进即可。
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    // 相对 start 经过的纳秒数
+    elapsed: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// 让时间前进 `duration`
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    /// 从创建到现在经过的时间
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed.load(Ordering::SeqCst))
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+}
@@ -0,0 +1,130 @@
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
+
+/// CLOCK（二次机会）缓存
+pub type ClockCache<K, V> = PolicyCache<K, V, ClockPolicy<K>>;
+
+impl<K, V> ClockCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(max_size: usize) -> Self {
+        PolicyCache::with_policy(ClockPolicy::new(max_size), max_size)
+    }
+}
+
+/// CLOCK 策略
+///
+/// 键放在一个环形数组中，命中只设置访问位，不移动键。
+/// 淘汰时指针沿环转动：访问位为真的清零并跳过，遇到访问位为假的即淘汰。
+pub struct ClockPolicy<K> {
+    // 键在环中的位置
+    index: HashMap<K, usize>,
+    slots: Vec<Option<ClockSlot<K>>>,
+    free: Vec<usize>,
+    hand: usize,
+}
+
+struct ClockSlot<K> {
+    key: K,
+    visited: bool,
+}
+
+impl<K> ClockPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            index: HashMap::new(),
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            hand: 0,
+        }
+    }
+}
+
+impl<K> EvictionPolicy<K> for ClockPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    /// 优先复用最近腾出的位置，也就是刚被淘汰的键所在的位置
+    fn on_insert(&mut self, key: &K) {
+        let slot = match self.free.pop() {
+            Some(idx) => idx,
+            None => {
+                self.slots.push(None);
+                self.slots.len() - 1
+            }
+        };
+        self.slots[slot] = Some(ClockSlot { key: key.clone(), visited: false });
+        self.index.insert(key.clone(), slot);
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if let Some(&idx) = self.index.get(key)
+            && let Some(slot) = &mut self.slots[idx]
+        {
+            slot.visited = true;
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(idx) = self.index.remove(key) {
+            self.slots[idx] = None;
+            self.free.push(idx);
+        }
+    }
+
+    /// 转动指针，淘汰第一个访问位为假的键
+    fn choose_victim(&mut self) -> Option<K> {
+        if self.index.is_empty() {
+            return None;
+        }
+        loop {
+            if self.hand >= self.slots.len() {
+                self.hand = 0;
+            }
+            let idx = self.hand;
+            self.hand += 1;
+            match &mut self.slots[idx] {
+                Some(slot) if slot.visited => slot.visited = false,
+                Some(_) => {
+                    let slot = self.slots[idx].take()?;
+                    self.index.remove(&slot.key);
+                    self.free.push(idx);
+                    return Some(slot.key);
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.slots.clear();
+        self.free.clear();
+        self.hand = 0;
+    }
+
+    /// 之后没有访问时指针依次淘汰的顺序：从指针处转一圈，先是访问位为假的键，再是访问位为真的键
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        let len = self.slots.len();
+        let mut unvisited = Vec::with_capacity(self.index.len());
+        let mut visited = Vec::new();
+        for slot in (0..len).filter_map(|i| self.slots[(self.hand + i) % len].as_ref()) {
+            if slot.visited {
+                visited.push(slot.key.clone());
+            } else {
+                unvisited.push(slot.key.clone());
+            }
+        }
+        unvisited.extend(visited);
+        Some(unvisited)
+    }
+}
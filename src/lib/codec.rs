@@ -0,0 +1,213 @@
+use std::fmt;
+
+/// 把键或值编码为字节，供文件存储和快照使用
+///
+/// 编码结果需要能自行界定长度，这样多个值可以依次写在同一段字节里。
+pub trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+}
+
+/// 从字节中解码，解码成功后 `input` 前移到下一个值的开头
+pub trait Decode: Sized {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError>;
+
+    /// 解码完整的一段字节，多余的字节视为错误
+    fn from_bytes(mut bytes: &[u8]) -> Result<Self, DecodeError> {
+        let value = Self::decode(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(DecodeError::TrailingBytes);
+        }
+        Ok(value)
+    }
+}
+
+/// 解码错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// 数据提前结束
+    UnexpectedEnd,
+    /// 解码完成后还有多余的字节
+    TrailingBytes,
+    /// 数据内容不合法
+    Invalid(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "数据提前结束"),
+            DecodeError::TrailingBytes => write!(f, "数据末尾有多余的字节"),
+            DecodeError::Invalid(reason) => write!(f, "数据不合法: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// 从 `input` 取出 `len` 个字节
+pub(crate) fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if input.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Ok(head)
+}
+
+// 整数按小端定长编码
+macro_rules! impl_codec_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                    let bytes = take(input, size_of::<$ty>())?;
+                    Ok(<$ty>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+// usize 和 isize 统一按 64 位编码，保证不同平台之间可以互相读取
+impl Encode for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+}
+
+impl Decode for usize {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        usize::try_from(u64::decode(input)?).map_err(|_| DecodeError::Invalid("usize 溢出"))
+    }
+}
+
+impl Encode for isize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as i64).encode(out);
+    }
+}
+
+impl Decode for isize {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        isize::try_from(i64::decode(input)?).map_err(|_| DecodeError::Invalid("isize 溢出"))
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::Invalid("bool 只能是 0 或 1")),
+        }
+    }
+}
+
+// 变长数据先写 u32 长度，再写内容
+impl Encode for [u8] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        out.extend_from_slice(self);
+    }
+}
+
+impl Encode for Vec<u8> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_slice().encode(out);
+    }
+}
+
+impl Decode for Vec<u8> {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let len = u32::decode(input)? as usize;
+        Ok(take(input, len)?.to_vec())
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_bytes().encode(out);
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().encode(out);
+    }
+}
+
+impl Decode for String {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        String::from_utf8(Vec::<u8>::decode(input)?).map_err(|_| DecodeError::Invalid("不是合法的 UTF-8"))
+    }
+}
+
+// CRC-32（IEEE 802.3，反射多项式 0xEDB88320）查找表，编译期生成
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// 计算 CRC-32 校验和，与 zlib 的 `crc32` 结果相同
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc = CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// 记录的头部：负载长度 u32 + 负载的 CRC-32 u32
+pub(crate) const FRAME_HEADER: usize = 8;
+
+/// 追加一条带长度和校验和的记录，负载由 `encode` 写入
+pub(crate) fn write_frame(out: &mut Vec<u8>, encode: impl FnOnce(&mut Vec<u8>)) {
+    let start = out.len();
+    out.extend_from_slice(&[0; FRAME_HEADER]);
+    encode(out);
+    let payload = &out[start + FRAME_HEADER..];
+    let len = payload.len() as u32;
+    let checksum = crc32(payload);
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+    out[start + 4..start + FRAME_HEADER].copy_from_slice(&checksum.to_le_bytes());
+}
+
+/// 读取 `bytes` 开头的一条记录并返回负载，记录不完整或校验失败时返回 None
+pub(crate) fn read_frame(bytes: &[u8]) -> Option<&[u8]> {
+    let header = bytes.get(..FRAME_HEADER)?;
+    let len = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+    let checksum = u32::from_le_bytes(header[4..].try_into().unwrap());
+    let payload = bytes.get(FRAME_HEADER..FRAME_HEADER + len)?;
+    (crc32(payload) == checksum).then_some(payload)
+}
@@ -0,0 +1,335 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::lib::cache::{Cache, CacheType, RemovalListener, boxed_cache};
+use crate::lib::clock::{Clock, MonotonicClock};
+use crate::lib::stats::CacheStats;
+
+struct Shard<K, V> {
+    cache: Mutex<Box<dyn Cache<K, V> + Send>>,
+    // 正在加载的键，同一个键只有一个线程在执行加载函数
+    loading: Mutex<HashMap<K, Arc<Flight<V>>>>,
+}
+
+/// 一次进行中的加载，等待者在 `done` 上等待结果
+struct Flight<V> {
+    state: Mutex<FlightState<V>>,
+    done: Condvar,
+}
+
+enum FlightState<V> {
+    Loading,
+    // 错误类型由调用方决定，这里擦除类型，等待者按自己的错误类型取回
+    Loaded(Result<V, Arc<dyn Any + Send + Sync>>),
+    // 加载函数 panic 了，等待者需要重试
+    Abandoned,
+}
+
+/// 线程安全的分片缓存
+///
+/// 键按哈希分到 N 个分片，每个分片是一个独立加锁的普通缓存，不同分片上的操作互不阻塞。
+/// 容量平均分给各分片，淘汰在分片内进行，因此整体的淘汰顺序只是近似的。
+pub struct ConcurrentCache<K, V> {
+    shards: Vec<Shard<K, V>>,
+    hasher: RandomState,
+}
+
+impl<K, V> ConcurrentCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    /// 创建 `shards` 个分片，每个分片使用 `cache_type` 指定的策略
+    pub fn new(cache_type: CacheType, shards: usize) -> Self {
+        Self::with_clock(cache_type, shards, MonotonicClock)
+    }
+
+    /// 使用指定时钟创建，所有分片共享同一个时钟
+    pub fn with_clock<C: Clock + Clone + 'static>(cache_type: CacheType, shards: usize, clock: C) -> Self {
+        let shards = shards.max(1);
+        let shard_type = split_capacity(&cache_type, shards);
+        Self {
+            shards: (0..shards)
+                .map(|_| Shard {
+                    cache: Mutex::new(new_shard(shard_type.clone(), clock.clone())),
+                    loading: Mutex::new(HashMap::new()),
+                })
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+}
+
+impl<K, V> ConcurrentCache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        self.shard(&key).insert(key, value);
+    }
+
+    pub fn insert_with_ttl(&self, key: K, value: V, ttl: Option<Duration>) {
+        self.shard(&key).insert_with_ttl(key, value, ttl);
+    }
+
+    pub fn insert_with_expiry(&self, key: K, value: V, ttl: Option<Duration>, tti: Option<Duration>) {
+        self.shard(&key).insert_with_expiry(key, value, ttl, tti);
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.shard(key).get(key)
+    }
+
+    pub fn peek(&self, key: &K) -> Option<V> {
+        self.shard(key).peek(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.shard(key).contains_key(key)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.shard(key).remove(key)
+    }
+
+    /// 读取值，未命中时调用 `load` 计算并写入
+    ///
+    /// 多个线程同时未命中同一个键时只有一个线程执行 `load`，其余线程等待并拿到同一个结果。
+    /// 加载期间不持有分片锁，其他键的读写不受影响。
+    pub fn get_or_insert_with<F>(&self, key: K, load: F) -> V
+    where
+        K: Clone,
+        F: FnOnce() -> V,
+    {
+        self.get_or_insert_with_ttl(key, None, load)
+    }
+
+    pub fn get_or_insert_with_ttl<F>(&self, key: K, ttl: Option<Duration>, load: F) -> V
+    where
+        K: Clone,
+        F: FnOnce() -> V,
+    {
+        match self.try_get_or_insert_with_ttl(key, ttl, || Ok::<V, std::convert::Infallible>(load())) {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// 读取值，未命中时调用可能失败的 `load`；加载失败时不写入任何内容
+    ///
+    /// 同样只有一个线程执行 `load`，等待的线程会收到同一个值或同一个错误。
+    /// 执行加载的线程 panic 时，等待的线程被唤醒并重新尝试，由其中一个接手加载。
+    pub fn try_get_or_insert_with<F, E>(&self, key: K, load: F) -> Result<V, E>
+    where
+        K: Clone,
+        F: FnOnce() -> Result<V, E>,
+        E: Clone + Send + Sync + 'static,
+    {
+        self.try_get_or_insert_with_ttl(key, None, load)
+    }
+
+    pub fn try_get_or_insert_with_ttl<F, E>(&self, key: K, ttl: Option<Duration>, load: F) -> Result<V, E>
+    where
+        K: Clone,
+        F: FnOnce() -> Result<V, E>,
+        E: Clone + Send + Sync + 'static,
+    {
+        let shard = self.shard_of(&key);
+        let mut load = Some(load);
+        loop {
+            if let Some(value) = lock(&shard.cache).get(&key) {
+                return Ok(value);
+            }
+            let (flight, leader) = {
+                let mut loading = lock(&shard.loading);
+                match loading.get(&key) {
+                    Some(flight) => (Arc::clone(flight), false),
+                    None => {
+                        let flight = Arc::new(Flight {
+                            state: Mutex::new(FlightState::Loading),
+                            done: Condvar::new(),
+                        });
+                        loading.insert(key.clone(), Arc::clone(&flight));
+                        (flight, true)
+                    }
+                }
+            };
+
+            if !leader {
+                let mut state = lock(&flight.state);
+                while matches!(*state, FlightState::Loading) {
+                    state = flight.done.wait(state).unwrap_or_else(PoisonError::into_inner);
+                }
+                match &*state {
+                    FlightState::Loaded(Ok(value)) => return Ok(value.clone()),
+                    // 错误类型与本次调用不同时无法转交，重新加载
+                    FlightState::Loaded(Err(error)) => match error.downcast_ref::<E>() {
+                        Some(error) => return Err(error.clone()),
+                        None => continue,
+                    },
+                    // 加载者 panic 了，重新尝试
+                    _ => continue,
+                }
+            }
+
+            let guard = FlightGuard { shard, key: &key, flight };
+            // 上一次加载可能刚好在我们查缓存之后完成；用 peek 复查，不重复计入未命中
+            if let Some(value) = lock(&shard.cache).peek(&key) {
+                guard.finish(Ok(value.clone()));
+                return Ok(value);
+            }
+            let start = Instant::now();
+            let result = (load.take().expect("加载函数只会被调用一次"))();
+            lock(&shard.cache).record_load(result.is_ok(), start.elapsed());
+            return match result {
+                Ok(value) => {
+                    lock(&shard.cache).insert_with_ttl(key.clone(), value.clone(), ttl);
+                    guard.finish(Ok(value.clone()));
+                    Ok(value)
+                }
+                Err(error) => {
+                    guard.finish(Err(Arc::new(error.clone())));
+                    Err(error)
+                }
+            };
+        }
+    }
+
+    /// 逐个分片清理过期条目，返回删除的总数
+    pub fn purge_expired(&self) -> usize {
+        self.shards.iter().map(|shard| lock(&shard.cache).purge_expired()).sum()
+    }
+
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            lock(&shard.cache).clear();
+        }
+    }
+
+    /// 为所有分片设置同一个删除监听器；监听器在持有分片锁时调用，不能再访问本缓存
+    pub fn set_removal_listener(&self, listener: RemovalListener<K, V>) {
+        for shard in &self.shards {
+            lock(&shard.cache).set_removal_listener(listener.clone());
+        }
+    }
+
+    /// 开启或关闭所有分片的统计
+    pub fn set_stats_enabled(&self, enabled: bool) {
+        for shard in &self.shards {
+            lock(&shard.cache).set_stats_enabled(enabled);
+        }
+    }
+
+    /// 各分片统计之和
+    pub fn stats(&self) -> CacheStats {
+        self.shards
+            .iter()
+            .map(|shard| lock(&shard.cache).stats())
+            .fold(CacheStats::default(), |total, stats| total + stats)
+    }
+
+    pub fn reset_stats(&self) {
+        for shard in &self.shards {
+            lock(&shard.cache).reset_stats();
+        }
+    }
+
+    /// 各分片未过期条目数之和；分片逐个加锁，并发写入时只是近似值
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| lock(&shard.cache).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| lock(&shard.cache).is_empty())
+    }
+
+    fn shard_of(&self, key: &K) -> &Shard<K, V> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+
+    fn shard(&self, key: &K) -> MutexGuard<'_, Box<dyn Cache<K, V> + Send>> {
+        lock(&self.shard_of(key).cache)
+    }
+}
+
+/// 负责结束一次加载；加载函数 panic 时在 drop 中把加载标记为放弃并唤醒等待者
+struct FlightGuard<'a, K, V>
+where
+    K: Hash + Eq,
+{
+    shard: &'a Shard<K, V>,
+    key: &'a K,
+    flight: Arc<Flight<V>>,
+}
+
+impl<K, V> FlightGuard<'_, K, V>
+where
+    K: Hash + Eq,
+{
+    fn finish(self, result: Result<V, Arc<dyn Any + Send + Sync>>) {
+        self.complete(FlightState::Loaded(result));
+    }
+
+    fn complete(&self, outcome: FlightState<V>) {
+        let mut state = lock(&self.flight.state);
+        if !matches!(*state, FlightState::Loading) {
+            return;
+        }
+        // 只移除自己登记的加载，之后可能已有新的加载登记了同一个键
+        let mut loading = lock(&self.shard.loading);
+        if loading.get(self.key).is_some_and(|flight| Arc::ptr_eq(flight, &self.flight)) {
+            loading.remove(self.key);
+        }
+        drop(loading);
+        *state = outcome;
+        self.flight.done.notify_all();
+    }
+}
+
+impl<K, V> Drop for FlightGuard<'_, K, V>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        self.complete(FlightState::Abandoned);
+    }
+}
+
+/// 加锁；持锁线程 panic 后分片里的数据仍然可用，不传播中毒
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn new_shard<K, V, C>(cache_type: CacheType, clock: C) -> Box<dyn Cache<K, V> + Send>
+where
+    K: Hash + Eq + Clone + Send + 'static,
+    V: Clone + Send + 'static,
+    C: Clock + 'static,
+{
+    boxed_cache!(cache_type, clock)
+}
+
+/// 把总容量向上取整平均分给各分片
+fn split_capacity(cache_type: &CacheType, shards: usize) -> CacheType {
+    let per_shard = |max_size: usize| max_size.div_ceil(shards);
+    match *cache_type {
+        CacheType::Basic => CacheType::Basic,
+        CacheType::Lru(max_size) => CacheType::Lru(per_shard(max_size)),
+        CacheType::Lfu(max_size) => CacheType::Lfu(per_shard(max_size)),
+        CacheType::Arc(max_size) => CacheType::Arc(per_shard(max_size)),
+        CacheType::TinyLfu(max_size) => CacheType::TinyLfu(per_shard(max_size)),
+        CacheType::Clock(max_size) => CacheType::Clock(per_shard(max_size)),
+        CacheType::Sieve(max_size) => CacheType::Sieve(per_shard(max_size)),
+        CacheType::S3Fifo(max_size) => CacheType::S3Fifo(per_shard(max_size)),
+    }
+}
@@ -0,0 +1,159 @@
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::lib::linkedlist::list_array::DoublyLinkedList;
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
+
+/// LFU 缓存
+pub type LfuCache<K, V> = PolicyCache<K, V, LfuPolicy<K>>;
+
+impl<K, V> LfuCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(max_size: usize) -> Self {
+        PolicyCache::with_policy(LfuPolicy::new(), max_size)
+    }
+
+    /// 当前键的访问频次，不存在时返回 None
+    pub fn frequency(&self, key: &K) -> Option<usize> {
+        self.policy().frequency(key)
+    }
+}
+
+/// LFU 策略：按访问频次分桶，淘汰频次最低桶中最久未使用的键
+///
+/// 频次桶按频次从低到高串成链表，每个桶内又是一条链表，头部为最近访问。
+/// 访问时键只会移到紧邻的下一个桶，最低频次桶总在链表头部，因此访问、插入和淘汰都是 O(1)。
+pub struct LfuPolicy<K> {
+    index: HashMap<K, LfuNode>,
+    buckets: DoublyLinkedList<Bucket<K>>,
+}
+
+struct Bucket<K> {
+    freq: usize,
+    keys: DoublyLinkedList<K>,
+}
+
+struct LfuNode {
+    // 所在桶在 buckets 链表中的下标
+    bucket: usize,
+    // 在桶内链表中的下标
+    node: usize,
+}
+
+impl<K> LfuPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            index: HashMap::new(),
+            buckets: DoublyLinkedList::new(),
+        }
+    }
+
+    /// 当前键的访问频次，不存在时返回 None
+    pub fn frequency(&self, key: &K) -> Option<usize> {
+        let item = self.index.get(key)?;
+        self.buckets.get(item.bucket).map(|bucket| bucket.freq)
+    }
+
+    fn bucket_mut(&mut self, idx: usize) -> &mut Bucket<K> {
+        self.buckets.get_mut(idx).unwrap()
+    }
+
+    /// 从桶中摘除节点，桶空时删除该桶
+    fn unlink(&mut self, bucket: usize, node: usize) {
+        let keys = &mut self.bucket_mut(bucket).keys;
+        keys.remove(node);
+        if keys.is_empty() {
+            self.buckets.remove(bucket);
+        }
+    }
+}
+
+impl<K> Default for LfuPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for LfuPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn on_insert(&mut self, key: &K) {
+        let bucket = match self.buckets.front() {
+            Some(front) if self.buckets.get(front).is_some_and(|bucket| bucket.freq == 1) => front,
+            _ => self.buckets.push_front(Bucket {
+                freq: 1,
+                keys: DoublyLinkedList::new(),
+            }),
+        };
+        let node = self.bucket_mut(bucket).keys.push_front(key.clone());
+        self.index.insert(key.clone(), LfuNode { bucket, node });
+    }
+
+    /// 把键从当前频次桶移到下一个频次桶的头部
+    fn on_access(&mut self, key: &K) {
+        let Some(&LfuNode { bucket, node }) = self.index.get(key) else {
+            return;
+        };
+        let freq = self.buckets.get(bucket).unwrap().freq;
+        let next = match self.buckets.next(bucket) {
+            Some(next) if self.buckets.get(next).is_some_and(|next| next.freq == freq + 1) => next,
+            _ => self.buckets.insert_after(
+                bucket,
+                Bucket {
+                    freq: freq + 1,
+                    keys: DoublyLinkedList::new(),
+                },
+            ),
+        };
+        // 先建好下一个桶再摘除，当前桶被删空也不影响新桶的位置
+        self.unlink(bucket, node);
+        let node = self.bucket_mut(next).keys.push_front(key.clone());
+        self.index.insert(key.clone(), LfuNode { bucket: next, node });
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(item) = self.index.remove(key) {
+            self.unlink(item.bucket, item.node);
+        }
+    }
+
+    /// 淘汰最低频次中最久未使用的键
+    fn choose_victim(&mut self) -> Option<K> {
+        let front = self.buckets.front()?;
+        let keys = &mut self.bucket_mut(front).keys;
+        let key = keys.pop_back()?;
+        if keys.is_empty() {
+            self.buckets.remove(front);
+        }
+        self.index.remove(&key);
+        Some(key)
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.buckets.clear();
+    }
+
+    /// 从最低频次到最高频次，同一频次内从最久未使用到最近使用
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        let mut keys = Vec::with_capacity(self.index.len());
+        for bucket in self.buckets.iter() {
+            let start = keys.len();
+            keys.extend(bucket.keys.iter().cloned());
+            keys[start..].reverse();
+        }
+        Some(keys)
+    }
+}
@@ -1,48 +1,173 @@
-struct Node {
-    value: i32,
+struct Node<T> {
+    value: Option<T>,
     prev: Option<usize>,
     next: Option<usize>,
 }
 
-struct DoublyLinkedList {
-    nodes: Vec<Node>,
+/// 基于 Vec 下标的双向链表，节点通过下标互相引用
+///
+/// 删除的槽位会进入空闲列表被复用，因此 `push_*` 返回的下标在节点被删除前一直有效，
+/// 可以由调用方保存下来做 O(1) 的删除和移动。
+pub struct DoublyLinkedList<T> {
+    nodes: Vec<Node<T>>,
     head: Option<usize>,
     tail: Option<usize>,
+    free: Vec<usize>,
+    len: usize,
 }
 
-impl DoublyLinkedList {
-    fn new() -> Self {
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> DoublyLinkedList<T> {
+    pub fn new() -> Self {
         Self {
             nodes: Vec::new(),
             head: None,
             tail: None,
+            free: Vec::new(),
+            len: 0,
         }
     }
 
-    fn push_back(&mut self, value: i32) {
-        let idx = self.nodes.len();
-        let new = Node {
-            value,
-            prev: self.tail,
-            next: None,
-        };
-        self.nodes.push(new);
-        if let Some(tail) = self.tail {
-            self.nodes[tail].next = Some(idx);
-        } else {
-            self.head = Some(idx);
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 头节点下标
+    pub fn front(&self) -> Option<usize> {
+        self.head
+    }
+
+    /// 尾节点下标
+    pub fn back(&self) -> Option<usize> {
+        self.tail
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&T> {
+        self.nodes.get(idx).and_then(|node| node.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.nodes.get_mut(idx).and_then(|node| node.value.as_mut())
+    }
+
+    /// 前一个节点（靠近头部）的下标
+    pub fn prev(&self, idx: usize) -> Option<usize> {
+        self.nodes[idx].prev
+    }
+
+    /// 后一个节点（靠近尾部）的下标
+    pub fn next(&self, idx: usize) -> Option<usize> {
+        self.nodes[idx].next
+    }
+
+    pub fn push_back(&mut self, value: T) -> usize {
+        let idx = self.alloc(value);
+        self.link_back(idx);
+        idx
+    }
+
+    pub fn push_front(&mut self, value: T) -> usize {
+        let idx = self.alloc(value);
+        self.link_front(idx);
+        idx
+    }
+
+    /// 在 `idx` 之后插入节点，返回新节点的下标
+    pub fn insert_after(&mut self, idx: usize, value: T) -> usize {
+        let new = self.alloc(value);
+        let next = self.nodes[idx].next;
+        self.nodes[new].prev = Some(idx);
+        self.nodes[new].next = next;
+        self.nodes[idx].next = Some(new);
+        match next {
+            Some(n) => self.nodes[n].prev = Some(new),
+            None => self.tail = Some(new),
         }
-        self.tail = Some(idx);
+        new
     }
 
-    fn push_front(&mut self, value: i32) {
-        let idx = self.nodes.len();
-        let new = Node {
-            value,
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.tail.and_then(|idx| self.remove(idx))
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.and_then(|idx| self.remove(idx))
+    }
+
+    /// 删除指定下标的节点，槽位留给后续插入复用
+    pub fn remove(&mut self, idx: usize) -> Option<T> {
+        let value = self.nodes.get_mut(idx)?.value.take()?;
+        self.unlink(idx);
+        self.free.push(idx);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// 把节点移动到头部，下标保持不变
+    pub fn move_to_front(&mut self, idx: usize) {
+        if self.head == Some(idx) {
+            return;
+        }
+        self.unlink(idx);
+        self.link_front(idx);
+    }
+
+    /// 把节点移动到尾部，下标保持不变
+    pub fn move_to_back(&mut self, idx: usize) {
+        if self.tail == Some(idx) {
+            return;
+        }
+        self.unlink(idx);
+        self.link_back(idx);
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.head = None;
+        self.tail = None;
+        self.len = 0;
+    }
+
+    /// 从头到尾遍历
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            cur: self.head,
+        }
+    }
+
+    fn alloc(&mut self, value: T) -> usize {
+        self.len += 1;
+        let node = Node {
+            value: Some(value),
             prev: None,
-            next: self.head,
+            next: None,
         };
-        self.nodes.push(new);
+        match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = node;
+                idx
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn link_front(&mut self, idx: usize) {
+        self.nodes[idx].prev = None;
+        self.nodes[idx].next = self.head;
         if let Some(head) = self.head {
             self.nodes[head].prev = Some(idx);
         } else {
@@ -51,44 +176,44 @@ impl DoublyLinkedList {
         self.head = Some(idx);
     }
 
-    fn pop_back(&mut self) -> Option<i32> {
-        self.tail.map(|idx| {
-            let value = self.nodes[idx].value;
-            let prev = self.nodes[idx].prev;
-            if let Some(p) = prev {
-                self.nodes[p].next = None;
-                self.tail = Some(p);
-            } else {
-                self.head = None;
-                self.tail = None;
-            }
-            value
-        })
-    }
-
-    fn pop_front(&mut self) -> Option<i32> {
-        self.head.map(|idx| {
-            let value = self.nodes[idx].value;
-            let next = self.nodes[idx].next;
-            if let Some(n) = next {
-                self.nodes[n].prev = None;
-                self.head = Some(n);
-            } else {
-                self.head = None;
-                self.tail = None;
-            }
-            value
-        })
+    fn link_back(&mut self, idx: usize) {
+        self.nodes[idx].prev = self.tail;
+        self.nodes[idx].next = None;
+        if let Some(tail) = self.tail {
+            self.nodes[tail].next = Some(idx);
+        } else {
+            self.head = Some(idx);
+        }
+        self.tail = Some(idx);
     }
 
-    fn iter(&self) -> Vec<i32> {
-        let mut res = Vec::new();
-        let mut cur = self.head;
-        while let Some(idx) = cur {
-            res.push(self.nodes[idx].value);
-            cur = self.nodes[idx].next;
+    fn unlink(&mut self, idx: usize) {
+        let prev = self.nodes[idx].prev.take();
+        let next = self.nodes[idx].next.take();
+        match prev {
+            Some(p) => self.nodes[p].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(n) => self.nodes[n].prev = prev,
+            None => self.tail = prev,
         }
-        res
+    }
+}
+
+pub struct Iter<'a, T> {
+    list: &'a DoublyLinkedList<T>,
+    cur: Option<usize>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.cur?;
+        let node = &self.list.nodes[idx];
+        self.cur = node.next;
+        node.value.as_ref()
     }
 }
 
@@ -101,9 +226,46 @@ mod tests {
         list.push_back(20);
         list.push_front(10);
         list.push_back(30);
-        println!("{:?}", list.iter()); // [10,20,30]
+        println!("{:?}", list.iter().collect::<Vec<_>>()); // [10,20,30]
         println!("{:?}", list.pop_front()); // Some(10)
         println!("{:?}", list.pop_back()); // Some(30)
-        println!("{:?}", list.iter()); // [20]
+        println!("{:?}", list.iter().collect::<Vec<_>>()); // [20]
+    }
+
+    #[test]
+    fn test_remove_and_move() {
+        let mut list = DoublyLinkedList::new();
+        let a = list.push_back(1);
+        let b = list.push_back(2);
+        let c = list.push_back(3);
+
+        list.move_to_front(c);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 1, 2]);
+
+        assert_eq!(list.remove(a), Some(1));
+        assert_eq!(list.remove(a), None);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 2]);
+
+        // 空闲槽位被复用
+        let d = list.push_front(4);
+        assert_eq!(d, a);
+        assert_eq!(list.get(b), Some(&2));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![4, 3, 2]);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_insert_after() {
+        let mut list = DoublyLinkedList::new();
+        let a = list.push_back(1);
+        let c = list.push_back(3);
+        list.insert_after(a, 2);
+        list.insert_after(c, 4);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_back(), Some(2));
+        assert_eq!(list.pop_back(), Some(1));
+        assert!(list.is_empty());
     }
 }
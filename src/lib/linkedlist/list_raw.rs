@@ -1,3 +1,6 @@
+// 链表的其他实现方式，仅作对比参考，缓存不使用
+#![allow(dead_code)]
+
 use std::ptr;
 
 struct Node {
@@ -0,0 +1,580 @@
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::lib::cache::{Cache, CacheError, EntryExpiry, RemovalListener};
+use crate::lib::clock::{Clock, MonotonicClock};
+use crate::lib::codec::{Decode, DecodeError, Encode, FRAME_HEADER, read_frame, take, write_frame};
+use crate::lib::concurrentcache::lock;
+use crate::lib::snapshot::{Snapshot, SnapshotError};
+use crate::lib::stats::CacheStats;
+use crate::lib::store::StoreError;
+
+// 记录类型
+const OP_INSERT: u8 = 1;
+const OP_INSERT_TTL: u8 = 2;
+const OP_INSERT_EXPIRY: u8 = 3;
+const OP_REMOVE: u8 = 4;
+const OP_CLEAR: u8 = 5;
+
+/// 日志写入后何时刷到磁盘
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// 每条记录都立即 fsync，最安全也最慢
+    Always,
+    /// 后台线程每秒 fsync 一次，断电最多丢失约一秒的写入
+    EverySecond,
+    /// 只写入操作系统缓冲区，由操作系统决定何时落盘
+    Never,
+}
+
+/// 启动时重放日志的结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// 成功重放的记录数
+    pub records: usize,
+    /// 末尾被截断或损坏而丢弃的字节数
+    pub discarded_bytes: u64,
+}
+
+/// 记录每次修改的缓存
+///
+/// `insert` 系列、`remove` 和 `clear` 成功后以带长度和校验和的记录追加到日志文件，
+/// 重启时先恢复快照再重放日志即可得到停机前的状态（见 [`LoggedCache::recover`]）。
+/// 日志末尾写了一半或损坏的记录会被检测出来并丢弃。
+///
+/// TTL 以绝对的系统时间记录，重放时已过期的写入被跳过；使用缓存默认 TTL 的写入在重放时重新计时。
+/// 日志只增不减，可以手动 [`rewrite`](LoggedCache::rewrite)，也可以用
+/// [`with_auto_rewrite`](LoggedCache::with_auto_rewrite) 在日志变大时自动重写。
+pub struct LoggedCache<K, V> {
+    cache: Box<dyn Cache<K, V>>,
+    path: PathBuf,
+    log: Arc<LogFile>,
+    fsync: FsyncPolicy,
+    syncer: Option<JoinHandle<()>>,
+    rewrite: Option<Rewrite>,
+    replay: ReplayReport,
+    clock: Arc<dyn Clock>,
+    // 日志文件当前的长度
+    log_len: u64,
+    // 上次重写完成（或打开）时日志的长度
+    base_len: u64,
+    // 自动重写的最小日志长度，`None` 表示不自动重写
+    auto_rewrite: Option<u64>,
+}
+
+/// 日志文件，`EverySecond` 模式下与后台线程共享
+struct LogFile {
+    file: Mutex<File>,
+    // 有还没 fsync 的写入
+    dirty: AtomicBool,
+    stop: Mutex<bool>,
+    wake: Condvar,
+}
+
+/// 进行中的后台重写
+struct Rewrite {
+    tmp: PathBuf,
+    // 存活条目部分的长度
+    len: u64,
+    // 重写开始后的新记录，重写完成时追加到新文件末尾
+    buffer: Vec<u8>,
+    handle: JoinHandle<io::Result<()>>,
+}
+
+impl<K, V> LoggedCache<K, V>
+where
+    K: Hash + Eq + Clone + Encode + Decode,
+    V: Clone + Encode + Decode,
+{
+    /// 打开日志文件（不存在时创建），把其中的记录重放到 `cache`，之后的修改追加到文件末尾
+    ///
+    /// 只截掉写了一半或校验失败的尾部；完整却无法解析的记录返回 `InvalidData` 错误，文件保持不变。
+    pub fn open(cache: Box<dyn Cache<K, V>>, path: impl AsRef<Path>, fsync: FsyncPolicy) -> io::Result<Self> {
+        Self::open_with_clock(cache, path, fsync, MonotonicClock)
+    }
+
+    /// 同 [`open`](LoggedCache::open)，用 `clock` 推算记录和重放时的系统时间，测试时传入与缓存相同的 `MockClock`
+    pub fn open_with_clock<C: Clock + 'static>(
+        mut cache: Box<dyn Cache<K, V>>,
+        path: impl AsRef<Path>,
+        fsync: FsyncPolicy,
+        clock: C,
+    ) -> io::Result<Self> {
+        let clock: Arc<dyn Clock> = Arc::new(clock);
+        let path = path.as_ref().to_path_buf();
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => return Err(error),
+        };
+        let (records, valid) = replay(&bytes, &mut *cache, wall_time(&*clock)).map_err(|(offset, error)| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("日志第 {} 字节处的记录无法解析: {}", offset, error))
+        })?;
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        // 截掉损坏的尾部，新记录紧接在最后一条完整记录之后
+        if valid < bytes.len() {
+            file.set_len(valid as u64)?;
+            file.sync_all()?;
+        }
+        let log = Arc::new(LogFile {
+            file: Mutex::new(file),
+            dirty: AtomicBool::new(false),
+            stop: Mutex::new(false),
+            wake: Condvar::new(),
+        });
+        let syncer = match fsync {
+            FsyncPolicy::EverySecond => {
+                let log = Arc::clone(&log);
+                Some(thread::spawn(move || log.sync_every_second()))
+            }
+            _ => None,
+        };
+        Ok(Self {
+            cache,
+            path,
+            log,
+            fsync,
+            syncer,
+            rewrite: None,
+            replay: ReplayReport {
+                records,
+                discarded_bytes: (bytes.len() - valid) as u64,
+            },
+            clock,
+            log_len: valid as u64,
+            base_len: valid as u64,
+            auto_rewrite: None,
+        })
+    }
+
+    /// 日志不小于 `min_len` 字节且至少是上次重写后的两倍时，在写入记录后自动开始后台重写
+    pub fn with_auto_rewrite(mut self, min_len: u64) -> Self {
+        self.auto_rewrite = Some(min_len);
+        self
+    }
+
+    /// 先恢复快照（存在时），再重放日志
+    pub fn recover(
+        mut cache: Box<dyn Cache<K, V>>,
+        snapshot: impl AsRef<Path>,
+        log: impl AsRef<Path>,
+        fsync: FsyncPolicy,
+    ) -> Result<Self, SnapshotError> {
+        if snapshot.as_ref().exists() {
+            cache.load_snapshot(snapshot)?;
+        }
+        Ok(Self::open(cache, log, fsync)?)
+    }
+
+    /// 保存快照并清空日志，之后只需重放快照之后的修改
+    pub fn checkpoint(&mut self, snapshot: impl AsRef<Path>) -> Result<usize, SnapshotError> {
+        self.wait_for_rewrite()?;
+        let saved = self.cache.save_snapshot(snapshot)?;
+        let file = lock(&self.log.file);
+        file.set_len(0)?;
+        file.sync_all()?;
+        self.log.dirty.store(false, Ordering::SeqCst);
+        self.log_len = 0;
+        self.base_len = 0;
+        Ok(saved)
+    }
+
+    /// 在后台把日志重写为只包含当前存活条目的记录，已有重写在进行时什么也不做
+    ///
+    /// 存活条目在当前线程编码，写文件和 fsync 在后台线程进行；
+    /// 期间的新记录照常写入旧日志，同时暂存起来，重写完成后追加到新日志末尾再替换旧文件。
+    /// 新日志以一条清空记录开头，在快照之上重放时快照里已删除的条目不会复活。
+    pub fn rewrite(&mut self) {
+        if self.rewrite.is_some() {
+            return;
+        }
+        let mut content = Vec::new();
+        write_frame(&mut content, |payload| payload.push(OP_CLEAR));
+        let now = wall_time(&*self.clock);
+        self.cache.for_each_entry(&mut |key, value, expiry| {
+            write_frame(&mut content, |payload| encode_live_entry(key, value, &expiry, now, payload));
+        });
+        let tmp = self.path.with_extension("rewrite");
+        let len = content.len() as u64;
+        let handle = {
+            let tmp = tmp.clone();
+            thread::spawn(move || {
+                let mut file = File::create(&tmp)?;
+                file.write_all(&content)?;
+                file.sync_all()
+            })
+        };
+        self.rewrite = Some(Rewrite {
+            tmp,
+            len,
+            buffer: Vec::new(),
+            handle,
+        });
+    }
+
+    /// 等待进行中的重写完成并替换日志文件
+    pub fn wait_for_rewrite(&mut self) -> io::Result<()> {
+        match self.rewrite.take() {
+            Some(rewrite) => self.finish_rewrite(rewrite),
+            None => Ok(()),
+        }
+    }
+
+    /// 是否有重写在进行
+    pub fn is_rewriting(&self) -> bool {
+        self.rewrite.is_some()
+    }
+
+    pub fn replay_report(&self) -> ReplayReport {
+        self.replay
+    }
+
+    /// 立即把日志刷到磁盘
+    pub fn sync(&self) -> io::Result<()> {
+        self.log.sync()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 写入一条记录，并在后台重写完成时替换日志文件
+    fn append(&mut self, encode: impl FnOnce(&mut Vec<u8>)) -> Result<(), CacheError> {
+        if self.rewrite.as_ref().is_some_and(|rewrite| rewrite.handle.is_finished()) {
+            self.wait_for_rewrite().map_err(StoreError::from)?;
+        }
+        let mut record = Vec::new();
+        write_frame(&mut record, encode);
+        {
+            let mut file = lock(&self.log.file);
+            file.write_all(&record).map_err(StoreError::from)?;
+            match self.fsync {
+                FsyncPolicy::Always => file.sync_data().map_err(StoreError::from)?,
+                FsyncPolicy::EverySecond => self.log.dirty.store(true, Ordering::SeqCst),
+                FsyncPolicy::Never => {}
+            }
+        }
+        self.log_len += record.len() as u64;
+        if let Some(rewrite) = &mut self.rewrite {
+            rewrite.buffer.extend_from_slice(&record);
+        } else if self
+            .auto_rewrite
+            .is_some_and(|min_len| self.log_len >= min_len && self.log_len >= self.base_len * 2)
+        {
+            self.rewrite();
+        }
+        Ok(())
+    }
+
+    fn finish_rewrite(&mut self, rewrite: Rewrite) -> io::Result<()> {
+        let result = rewrite.handle.join().unwrap_or_else(|_| Err(io::Error::other("日志重写线程 panic")));
+        let result = result.and_then(|()| {
+            let mut file = OpenOptions::new().append(true).open(&rewrite.tmp)?;
+            file.write_all(&rewrite.buffer)?;
+            file.sync_all()?;
+            // 持有锁完成替换，保证之后的记录写入新文件
+            let mut current = lock(&self.log.file);
+            fs::rename(&rewrite.tmp, &self.path)?;
+            *current = file;
+            self.log_len = rewrite.len + rewrite.buffer.len() as u64;
+            self.base_len = self.log_len;
+            Ok(())
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&rewrite.tmp);
+        }
+        result
+    }
+}
+
+impl<K, V> Cache<K, V> for LoggedCache<K, V>
+where
+    K: Hash + Eq + Clone + Encode + Decode,
+    V: Clone + Encode + Decode,
+{
+    fn insert(&mut self, key: K, value: V) {
+        let _ = self.try_insert(key, value);
+    }
+
+    fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) {
+        let _ = self.try_insert_with_ttl(key, value, ttl);
+    }
+
+    fn insert_with_expiry(&mut self, key: K, value: V, ttl: Option<Duration>, tti: Option<Duration>) {
+        let _ = self.try_insert_with_expiry(key, value, ttl, tti);
+    }
+
+    fn try_insert(&mut self, key: K, value: V) -> Result<(), CacheError> {
+        self.cache.try_insert(key.clone(), value.clone())?;
+        self.append(|out| {
+            out.push(OP_INSERT);
+            key.encode(out);
+            value.encode(out);
+        })
+    }
+
+    fn try_insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Result<(), CacheError> {
+        self.cache.try_insert_with_ttl(key.clone(), value.clone(), ttl)?;
+        let deadline = ttl.map(|ttl| wall_time(&*self.clock) + ttl);
+        self.append(|out| {
+            out.push(OP_INSERT_TTL);
+            key.encode(out);
+            value.encode(out);
+            encode_optional_deadline(deadline, out);
+        })
+    }
+
+    fn try_insert_with_expiry(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+    ) -> Result<(), CacheError> {
+        self.cache.try_insert_with_expiry(key.clone(), value.clone(), ttl, tti)?;
+        let deadline = ttl.map(|ttl| wall_time(&*self.clock) + ttl);
+        self.append(|out| {
+            out.push(OP_INSERT_EXPIRY);
+            key.encode(out);
+            value.encode(out);
+            encode_optional_deadline(deadline, out);
+            encode_optional_duration(tti, out);
+        })
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.cache.get(key)
+    }
+
+    fn peek(&self, key: &K) -> Option<V> {
+        self.cache.peek(key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.cache.contains_key(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.cache.remove(key);
+        let _ = self.append(|out| {
+            out.push(OP_REMOVE);
+            key.encode(out);
+        });
+        value
+    }
+
+    fn purge_expired(&mut self) -> usize {
+        self.cache.purge_expired()
+    }
+
+    fn clear(&mut self) {
+        self.cache.clear();
+        let _ = self.append(|out| out.push(OP_CLEAR));
+    }
+
+    fn for_each_entry(&self, visit: &mut dyn FnMut(&K, &V, EntryExpiry)) {
+        self.cache.for_each_entry(visit);
+    }
+
+    fn set_removal_listener(&mut self, listener: RemovalListener<K, V>) {
+        self.cache.set_removal_listener(listener);
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    fn set_stats_enabled(&mut self, enabled: bool) {
+        self.cache.set_stats_enabled(enabled);
+    }
+
+    fn reset_stats(&mut self) {
+        self.cache.reset_stats();
+    }
+
+    fn record_load(&mut self, success: bool, elapsed: Duration) {
+        self.cache.record_load(success, elapsed);
+    }
+
+    fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+impl<K, V> Drop for LoggedCache<K, V> {
+    /// 放弃未完成的重写（旧日志仍然完整），停止后台线程并刷盘
+    fn drop(&mut self) {
+        if let Some(rewrite) = self.rewrite.take() {
+            let _ = rewrite.handle.join();
+            let _ = fs::remove_file(&rewrite.tmp);
+        }
+        *lock(&self.log.stop) = true;
+        self.log.wake.notify_all();
+        if let Some(syncer) = self.syncer.take() {
+            let _ = syncer.join();
+        }
+        if self.fsync != FsyncPolicy::Never {
+            let _ = self.log.sync();
+        }
+    }
+}
+
+impl LogFile {
+    fn sync(&self) -> io::Result<()> {
+        self.dirty.store(false, Ordering::SeqCst);
+        lock(&self.file).sync_data()
+    }
+
+    fn sync_every_second(&self) {
+        let mut stop = lock(&self.stop);
+        while !*stop {
+            stop = self
+                .wake
+                .wait_timeout(stop, Duration::from_secs(1))
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+            if self.dirty.load(Ordering::SeqCst) {
+                let _ = self.sync();
+            }
+        }
+    }
+}
+
+/// 把日志中的记录依次重放到缓存，返回重放的记录数和有效部分的长度
+///
+/// 遇到不完整或校验失败的记录时停止，之后的内容都视为损坏的尾部。
+/// 校验通过却无法解析的记录（例如新版本写入的记录类型或键值类型不符）不是损坏，
+/// 返回它的偏移和错误，不丢弃之后的记录。
+fn replay<K, V>(
+    bytes: &[u8],
+    cache: &mut dyn Cache<K, V>,
+    now: SystemTime,
+) -> Result<(usize, usize), (usize, DecodeError)>
+where
+    K: Hash + Eq + Decode,
+    V: Clone + Decode,
+{
+    let mut records = 0;
+    let mut offset = 0;
+    while let Some(payload) = read_frame(&bytes[offset..]) {
+        apply(payload, cache, now).map_err(|error| (offset, error))?;
+        records += 1;
+        offset += FRAME_HEADER + payload.len();
+    }
+    Ok((records, offset))
+}
+
+fn apply<K, V>(mut payload: &[u8], cache: &mut dyn Cache<K, V>, now: SystemTime) -> Result<(), DecodeError>
+where
+    K: Hash + Eq + Decode,
+    V: Clone + Decode,
+{
+    let input = &mut payload;
+    match take(input, 1)?[0] {
+        OP_INSERT => {
+            let (key, value) = (K::decode(input)?, V::decode(input)?);
+            ensure_consumed(input)?;
+            cache.insert(key, value);
+        }
+        op @ (OP_INSERT_TTL | OP_INSERT_EXPIRY) => {
+            let (key, value) = (K::decode(input)?, V::decode(input)?);
+            let deadline = decode_optional_deadline(input)?;
+            let tti = if op == OP_INSERT_EXPIRY {
+                Some(decode_optional_duration(input)?)
+            } else {
+                None
+            };
+            ensure_consumed(input)?;
+            // 重放时已经过期的写入等同于没有写入，但要覆盖掉之前的值
+            let ttl = match deadline {
+                Some(deadline) => match deadline.duration_since(now) {
+                    Ok(remaining) if !remaining.is_zero() => Some(remaining),
+                    _ => {
+                        cache.remove(&key);
+                        return Ok(());
+                    }
+                },
+                None => None,
+            };
+            match tti {
+                Some(tti) => cache.insert_with_expiry(key, value, ttl, tti),
+                None => cache.insert_with_ttl(key, value, ttl),
+            }
+        }
+        OP_REMOVE => {
+            let key = K::decode(input)?;
+            ensure_consumed(input)?;
+            cache.remove(&key);
+        }
+        OP_CLEAR => {
+            ensure_consumed(input)?;
+            cache.clear();
+        }
+        _ => return Err(DecodeError::Invalid("未知的日志记录类型")),
+    }
+    Ok(())
+}
+
+/// 按 `clock` 推算的系统时间
+///
+/// 进程内以第一次调用时的系统时间为基准，之后按时钟流逝的时间推算，不受系统时间调整影响。
+fn wall_time(clock: &dyn Clock) -> SystemTime {
+    static BASE: OnceLock<(SystemTime, Instant)> = OnceLock::new();
+    let (wall, instant) = *BASE.get_or_init(|| (SystemTime::now(), Instant::now()));
+    let now = clock.now();
+    match now.checked_duration_since(instant) {
+        Some(elapsed) => wall + elapsed,
+        None => wall - instant.duration_since(now),
+    }
+}
+
+fn ensure_consumed(input: &[u8]) -> Result<(), DecodeError> {
+    if input.is_empty() { Ok(()) } else { Err(DecodeError::TrailingBytes) }
+}
+
+/// 重写时把一个存活条目编码为带绝对过期时间的写入
+fn encode_live_entry<K: Encode, V: Encode>(key: &K, value: &V, expiry: &EntryExpiry, now: SystemTime, out: &mut Vec<u8>) {
+    out.push(OP_INSERT_EXPIRY);
+    key.encode(out);
+    value.encode(out);
+    encode_optional_deadline(expiry.ttl_remaining.map(|remaining| now + remaining), out);
+    encode_optional_duration(expiry.idle, out);
+}
+
+fn encode_optional_duration(duration: Option<Duration>, out: &mut Vec<u8>) {
+    match duration {
+        Some(duration) => {
+            true.encode(out);
+            (duration.as_nanos().min(u64::MAX as u128) as u64).encode(out);
+        }
+        None => false.encode(out),
+    }
+}
+
+fn decode_optional_duration(input: &mut &[u8]) -> Result<Option<Duration>, DecodeError> {
+    if bool::decode(input)? {
+        Ok(Some(Duration::from_nanos(u64::decode(input)?)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 过期时刻按距 UNIX 纪元的时长编码
+fn encode_optional_deadline(deadline: Option<SystemTime>, out: &mut Vec<u8>) {
+    encode_optional_duration(deadline.map(|deadline| deadline.duration_since(UNIX_EPOCH).unwrap_or_default()), out);
+}
+
+fn decode_optional_deadline(input: &mut &[u8]) -> Result<Option<SystemTime>, DecodeError> {
+    Ok(decode_optional_duration(input)?.map(|since_epoch| UNIX_EPOCH + since_epoch))
+}
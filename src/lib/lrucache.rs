@@ -1,73 +1,89 @@
 
 use std::collections::HashMap;
-use std::time::{SystemTime, Duration};
 use std::hash::Hash;
 
-use crate::lib::cache::{Cache, CacheEntry};
+use crate::lib::linkedlist::list_array::DoublyLinkedList;
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
 
-
-pub struct LruCache<K, V> {
-    data: HashMap<K, CacheEntry<V>>,
-    default_ttl: Option<Duration>,
-    _max_size: usize,
-}
+/// LRU 缓存
+pub type LruCache<K, V> = PolicyCache<K, V, LruPolicy<K>>;
 
 impl<K, V> LruCache<K, V>
 where
-    K: Hash + Eq,
+    K: Hash + Eq + Clone,
     V: Clone,
 {
     pub fn new(max_size: usize) -> Self {
+        PolicyCache::with_policy(LruPolicy::new(), max_size)
+    }
+}
+
+/// LRU 策略：链表头部为最近使用，尾部为最久未使用
+pub struct LruPolicy<K> {
+    // 键在 order 链表中的下标
+    index: HashMap<K, usize>,
+    order: DoublyLinkedList<K>,
+}
+
+impl<K> LruPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
         Self {
-            data: HashMap::new(),
-            default_ttl: None,
-            _max_size: max_size,
+            index: HashMap::new(),
+            order: DoublyLinkedList::new(),
         }
     }
 }
 
-impl<K, V> Cache<K, V> for LruCache<K, V>
+impl<K> Default for LruPolicy<K>
 where
-    K: Hash + Eq,
-    V: Clone,
+    K: Hash + Eq + Clone,
 {
-    fn insert(&mut self, key: K, value: V) {
-        self.insert_with_ttl(key, value, self.default_ttl);
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) {
-        let expiry = ttl.map(|duration| SystemTime::now() + duration);
-        self.data.insert(key, CacheEntry { value, expiry });
+impl<K> EvictionPolicy<K> for LruPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn on_insert(&mut self, key: &K) {
+        let node = self.order.push_front(key.clone());
+        self.index.insert(key.clone(), node);
     }
 
-    fn get(&self, key: &K) -> Option<V> {
-        match self.data.get(key) {
-            Some(entry) => {
-                // 检查是否过期
-                if let Some(expiry) = entry.expiry {
-                    if SystemTime::now() > expiry {
-                        return None; // 已过期
-                    }
-                }
-                Some(entry.value.clone())
-            }
-            None => None,
+    fn on_access(&mut self, key: &K) {
+        if let Some(&node) = self.index.get(key) {
+            self.order.move_to_front(node);
         }
     }
 
-    fn remove(&mut self, key: &K) -> Option<V> {
-        self.data.remove(key).map(|entry| entry.value)
+    fn on_remove(&mut self, key: &K) {
+        if let Some(node) = self.index.remove(key) {
+            self.order.remove(node);
+        }
     }
 
-    fn clear(&mut self) {
-        self.data.clear();
+    /// 淘汰最久未使用的键
+    fn choose_victim(&mut self) -> Option<K> {
+        let key = self.order.pop_back()?;
+        self.index.remove(&key);
+        Some(key)
     }
 
-    fn len(&self) -> usize {
-        self.data.len()
+    fn clear(&mut self) {
+        self.index.clear();
+        self.order.clear();
     }
 
-    fn is_empty(&self) -> bool {
-        self.data.is_empty()
+    /// 从最久未使用到最近使用
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        let mut keys: Vec<K> = self.order.iter().cloned().collect();
+        keys.reverse();
+        Some(keys)
     }
-}
\ No newline at end of file
+}
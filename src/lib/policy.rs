@@ -0,0 +1,42 @@
+/// 淘汰策略 trait
+///
+/// 策略只维护键的淘汰顺序，不持有值；存储和过期由 [`PolicyCache`] 负责，
+/// 它在键进出存储时通知策略，并在超出容量时向策略要一个淘汰对象。
+///
+/// [`PolicyCache`]: crate::lib::policycache::PolicyCache
+pub trait EvictionPolicy<K> {
+    /// 新键写入存储前、为它腾出空间之前调用，策略可以据此调整淘汰对象的选择
+    fn before_insert(&mut self, _key: &K) {}
+    /// 新键写入存储后调用
+    fn on_insert(&mut self, key: &K);
+    /// 已有的键被读取或覆盖写入时调用
+    fn on_access(&mut self, key: &K);
+    /// 键被显式删除或因过期移出存储时调用；`choose_victim` 返回的键不会再收到此通知
+    fn on_remove(&mut self, key: &K);
+    /// 选出一个淘汰对象并把它从策略中移除，没有可淘汰的键时返回 None
+    fn choose_victim(&mut self) -> Option<K>;
+    /// 清空所有状态
+    fn clear(&mut self);
+    /// 按淘汰先后排列的所有键，最先被淘汰的在前；不便给出顺序的策略返回 None
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        None
+    }
+}
+
+/// 从不淘汰的策略，用于无容量上限的缓存
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoEviction;
+
+impl<K> EvictionPolicy<K> for NoEviction {
+    fn on_insert(&mut self, _key: &K) {}
+
+    fn on_access(&mut self, _key: &K) {}
+
+    fn on_remove(&mut self, _key: &K) {}
+
+    fn choose_victim(&mut self) -> Option<K> {
+        None
+    }
+
+    fn clear(&mut self) {}
+}
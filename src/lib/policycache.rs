@@ -0,0 +1,734 @@
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::hash::Hash;
+
+use crate::lib::cache::{Cache, CacheEntry, CacheError, EntryExpiry, RemovalCause, RemovalListener};
+use crate::lib::clock::{Clock, MonotonicClock};
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::stats::CacheStats;
+use crate::lib::timerwheel::TimerWheel;
+use crate::lib::weigher::Weigher;
+
+/// 由淘汰策略驱动的缓存
+///
+/// 负责存储条目和 TTL 过期判断，淘汰顺序完全交给策略 `P`。
+/// 各内置缓存（`LruCache`、`LfuCache` 等）都是它搭配不同策略的类型别名，
+/// 自定义策略只需实现 [`EvictionPolicy`]。
+pub struct PolicyCache<K, V, P> {
+    data: HashMap<K, CacheEntry<V>>,
+    policy: P,
+    clock: Arc<dyn Clock>,
+    // 带 TTL 的键按过期时间登记在时间轮上
+    timers: TimerWheel<K>,
+    default_ttl: Option<Duration>,
+    default_tti: Option<Duration>,
+    // None 表示不限容量
+    max_size: Option<usize>,
+    // 按权重限制容量；未设置称重器时每个条目重 1
+    weigher: Option<Box<dyn Weigher<K, V>>>,
+    max_weight: Option<u64>,
+    total_weight: u64,
+    // 最早的过期时间；删除条目时不更新，只会偏早
+    next_expiry: Option<Instant>,
+    listener: Option<RemovalListener<K, V>>,
+    // None 表示没有开启统计
+    stats: Option<CacheStats>,
+    // 标签到带该标签的键
+    tag_index: HashMap<String, HashSet<K>>,
+}
+
+impl<K, V, P> PolicyCache<K, V, P>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    P: EvictionPolicy<K>,
+{
+    /// 最多保存 `max_size` 个条目，超出时由策略选出淘汰对象
+    pub fn with_policy(policy: P, max_size: usize) -> Self {
+        Self {
+            data: HashMap::new(),
+            policy,
+            clock: Arc::new(MonotonicClock),
+            timers: TimerWheel::new(),
+            default_ttl: None,
+            default_tti: None,
+            max_size: Some(max_size),
+            weigher: None,
+            max_weight: None,
+            total_weight: 0,
+            next_expiry: None,
+            listener: None,
+            stats: None,
+            tag_index: HashMap::new(),
+        }
+    }
+
+    /// 不限容量，策略只用于记录访问
+    pub fn unbounded(policy: P) -> Self {
+        Self {
+            data: HashMap::new(),
+            policy,
+            clock: Arc::new(MonotonicClock),
+            timers: TimerWheel::new(),
+            default_ttl: None,
+            default_tti: None,
+            max_size: None,
+            weigher: None,
+            max_weight: None,
+            total_weight: 0,
+            next_expiry: None,
+            listener: None,
+            stats: None,
+            tag_index: HashMap::new(),
+        }
+    }
+
+    /// 替换时钟，应在插入数据之前调用
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.timers = TimerWheel::starting_at(clock.now());
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// 设置默认存活时间，`insert` 写入的条目都会带上
+    pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// 设置默认空闲时间，`insert` 和 `insert_with_ttl` 写入的条目都会带上
+    pub fn with_time_to_idle(mut self, tti: Duration) -> Self {
+        self.default_tti = Some(tti);
+        self
+    }
+
+    /// 按权重限制容量，总权重超过 `max_weight` 时由策略选出淘汰对象
+    ///
+    /// 与条目数上限同时生效；只想按权重限制时可以用 [`PolicyCache::unbounded`] 创建。
+    /// 策略选不出淘汰对象时（例如不淘汰的 `BasicCache`），放不下的写入返回 [`CacheError::CacheFull`]。
+    /// ARC、W-TinyLFU 等策略内部仍按构造时的条目数划分区域，应传入预估的条目数。
+    pub fn with_max_weight<W: Weigher<K, V> + 'static>(mut self, max_weight: u64, weigher: W) -> Self {
+        self.weigher = Some(Box::new(weigher));
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    /// 当前所有条目（包括尚未清理的过期条目）的总权重
+    pub fn weight(&self) -> u64 {
+        self.total_weight
+    }
+
+    /// 设置删除监听器
+    pub fn with_removal_listener(mut self, listener: RemovalListener<K, V>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    /// 开启统计
+    pub fn with_stats(mut self) -> Self {
+        self.stats = Some(CacheStats::default());
+        self
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// 插入带标签的条目，之后可以用 [`invalidate_tag`](Self::invalidate_tag) 按标签批量删除
+    ///
+    /// 覆盖已有条目时旧标签被新标签取代。
+    pub fn insert_with_tags<I, T>(&mut self, key: K, value: V, tags: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.insert_with_tags_and_ttl(key, value, self.default_ttl, tags);
+    }
+
+    /// 插入带标签和 TTL 的条目
+    pub fn insert_with_tags_and_ttl<I, T>(&mut self, key: K, value: V, ttl: Option<Duration>, tags: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let mut tags: Vec<String> = tags.into_iter().map(Into::into).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        let _ = self.insert_entry(key, value, ttl, self.default_tti, tags);
+    }
+
+    /// 删除所有带 `tag` 的条目，返回删除的未过期条目数；耗时与匹配的条目数成正比
+    pub fn invalidate_tag(&mut self, tag: &str) -> usize {
+        let Some(keys) = self.tag_index.remove(tag) else {
+            return 0;
+        };
+        let now = self.clock.now();
+        let mut removed = 0;
+        for key in keys {
+            let cause = match self.data.get(&key) {
+                Some(entry) if entry.is_expired(now) => RemovalCause::Expired,
+                Some(_) => RemovalCause::Explicit,
+                None => continue,
+            };
+            self.remove_entry(&key, cause);
+            if cause == RemovalCause::Explicit {
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// 带 `tag` 的条目数，包括还没清理的过期条目
+    pub fn tagged_len(&self, tag: &str) -> usize {
+        self.tag_index.get(tag).map_or(0, HashSet::len)
+    }
+
+    /// 取得 `key` 对应的条目，用于原地读取、修改或插入，类似 `HashMap::entry`
+    ///
+    /// 找到未过期的条目时计为一次访问（命中），否则计为未命中；已过期的条目按过期删除后视为空位。
+    /// 称重器只在插入时计算重量，通过 `&mut V` 原地修改不会重新称重。
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, P> {
+        let now = self.clock.now();
+        let live = match self.data.get_mut(&key) {
+            Some(entry) if entry.is_expired(now) => {
+                self.remove_entry(&key, RemovalCause::Expired);
+                false
+            }
+            Some(entry) => {
+                entry.last_access = now;
+                self.policy.on_access(&key);
+                true
+            }
+            None => false,
+        };
+        if let Some(stats) = &mut self.stats {
+            if live {
+                stats.hits += 1;
+            } else {
+                stats.misses += 1;
+            }
+        }
+        if live {
+            Entry::Occupied(OccupiedEntry { cache: self, key })
+        } else {
+            Entry::Vacant(VacantEntry { cache: self, key })
+        }
+    }
+
+    /// 插入条目，`tags` 已去重
+    fn insert_entry(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+        tags: Vec<String>,
+    ) -> Result<(), CacheError> {
+        if self.max_size == Some(0) {
+            return Ok(());
+        }
+        let weight = self.weigher.as_ref().map_or(1, |weigher| weigher.weigh(&key, &value));
+        if let Some(max_weight) = self.max_weight
+            && weight > max_weight
+        {
+            // 覆盖写入被拒绝时删除旧值，之后不会再读到过时的值
+            self.remove_entry(&key, RemovalCause::Replaced);
+            return Err(CacheError::EntryTooHeavy { weight, max_weight });
+        }
+        // 插入时顺带清理到期的条目，时间轮只取出到期的键，均摊 O(1)
+        let now = self.clock.now();
+        if self.may_have_expired(now) {
+            self.purge_expired();
+        }
+        let entry = CacheEntry {
+            value,
+            expiry: ttl.map(|duration| now + duration),
+            idle: tti,
+            last_access: now,
+            weight,
+            tags,
+        };
+        let deadline = entry.deadline();
+
+        if let Some(existing) = self.data.get_mut(&key) {
+            self.total_weight = self.total_weight - existing.weight + weight;
+            unindex_tags(&mut self.tag_index, &key, &existing.tags);
+            index_tags(&mut self.tag_index, &key, &entry.tags);
+            let old = mem::replace(existing, entry);
+            self.record_insert(&key, deadline);
+            self.policy.on_access(&key);
+            self.notify(&key, &old.value, RemovalCause::Replaced);
+            // 新值更重时可能需要淘汰其他条目
+            self.make_room(0, 0);
+            if self.exceeds_capacity(0, 0) {
+                // 策略没有可淘汰的条目（例如不淘汰的 BasicCache），新值放不下
+                self.remove_entry(&key, RemovalCause::Evicted);
+                return Err(CacheError::CacheFull);
+            }
+            return Ok(());
+        }
+
+        self.policy.before_insert(&key);
+        self.make_room(1, weight);
+        if self.exceeds_capacity(1, weight) {
+            return Err(CacheError::CacheFull);
+        }
+        self.record_insert(&key, deadline);
+        self.policy.on_insert(&key);
+        index_tags(&mut self.tag_index, &key, &entry.tags);
+        self.data.insert(key, entry);
+        self.total_weight += weight;
+        Ok(())
+    }
+
+    /// 计入插入统计，并按新条目的过期时间登记或取消时间轮
+    fn record_insert(&mut self, key: &K, deadline: Option<Instant>) {
+        if let Some(stats) = &mut self.stats {
+            stats.inserts += 1;
+        }
+        match deadline {
+            Some(deadline) => self.schedule(key.clone(), deadline),
+            None => self.timers.cancel(key),
+        }
+    }
+
+    /// 是否可能有条目已经过期
+    fn may_have_expired(&self, now: Instant) -> bool {
+        self.next_expiry.is_some_and(|expiry| now > expiry)
+    }
+
+    /// 在时间轮上登记过期时间
+    fn schedule(&mut self, key: K, deadline: Instant) {
+        self.timers.schedule(key, deadline);
+        self.next_expiry = Some(self.next_expiry.map_or(deadline, |next| next.min(deadline)));
+    }
+
+    /// 从存储、时间轮和标签索引中取出条目，不通知策略
+    fn take(&mut self, key: &K) -> Option<CacheEntry<V>> {
+        let entry = self.data.remove(key)?;
+        self.timers.cancel(key);
+        unindex_tags(&mut self.tag_index, key, &entry.tags);
+        self.total_weight -= entry.weight;
+        Some(entry)
+    }
+
+    /// 从存储、时间轮和策略中删除一个条目，并通知监听器
+    fn remove_entry(&mut self, key: &K, cause: RemovalCause) -> Option<CacheEntry<V>> {
+        let entry = self.take(key)?;
+        self.policy.on_remove(key);
+        self.notify(key, &entry.value, cause);
+        Some(entry)
+    }
+
+    /// 条目离开缓存：计入统计并通知监听器
+    fn notify(&mut self, key: &K, value: &V, cause: RemovalCause) {
+        if let Some(stats) = &mut self.stats {
+            stats.record_removal(cause);
+        }
+        if let Some(listener) = &self.listener {
+            listener(key, value, cause);
+        }
+    }
+
+    /// 再加入 `count` 个条目、`weight` 权重后是否超出容量
+    fn exceeds_capacity(&self, count: usize, weight: u64) -> bool {
+        self.max_size.is_some_and(|max_size| self.data.len() + count > max_size)
+            || self.max_weight.is_some_and(|max_weight| self.total_weight + weight > max_weight)
+    }
+
+    /// 淘汰条目直到能放下 `count` 个条目、`weight` 权重；过期条目在插入前已经清理过
+    fn make_room(&mut self, count: usize, weight: u64) {
+        while self.exceeds_capacity(count, weight) {
+            match self.policy.choose_victim() {
+                Some(victim) => {
+                    if let Some(entry) = self.take(&victim) {
+                        self.notify(&victim, &entry.value, RemovalCause::Evicted);
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl<K, V, P> Cache<K, V> for PolicyCache<K, V, P>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    P: EvictionPolicy<K>,
+{
+    fn insert(&mut self, key: K, value: V) {
+        self.insert_with_ttl(key, value, self.default_ttl);
+    }
+
+    fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) {
+        self.insert_with_expiry(key, value, ttl, self.default_tti);
+    }
+
+    fn insert_with_expiry(&mut self, key: K, value: V, ttl: Option<Duration>, tti: Option<Duration>) {
+        let _ = self.try_insert_with_expiry(key, value, ttl, tti);
+    }
+
+    fn try_insert(&mut self, key: K, value: V) -> Result<(), CacheError> {
+        self.try_insert_with_ttl(key, value, self.default_ttl)
+    }
+
+    fn try_insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Result<(), CacheError> {
+        self.try_insert_with_expiry(key, value, ttl, self.default_tti)
+    }
+
+    fn try_insert_with_expiry(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+    ) -> Result<(), CacheError> {
+        self.insert_entry(key, value, ttl, tti, Vec::new())
+    }
+
+    fn get(&mut self, key: &K) -> Option<V> {
+        let now = self.clock.now();
+        // 与插入一样顺带清理到期的条目，之后 len 在下一个过期时间之前都是 O(1)
+        if self.may_have_expired(now) {
+            self.purge_expired();
+        }
+        let value = match self.data.get_mut(key) {
+            Some(entry) if entry.is_expired(now) => {
+                // 访问到过期条目时顺便删除
+                self.remove_entry(key, RemovalCause::Expired);
+                None
+            }
+            Some(entry) => {
+                // 读取会延长空闲期限，时间轮里的旧期限到期时再重新登记
+                entry.last_access = now;
+                let value = entry.value.clone();
+                self.policy.on_access(key);
+                Some(value)
+            }
+            None => None,
+        };
+        if let Some(stats) = &mut self.stats {
+            if value.is_some() {
+                stats.hits += 1;
+            } else {
+                stats.misses += 1;
+            }
+        }
+        value
+    }
+
+    fn peek(&self, key: &K) -> Option<V> {
+        match self.data.get(key) {
+            Some(entry) => {
+                // 检查是否过期
+                if entry.is_expired(self.clock.now()) {
+                    return None; // 已过期
+                }
+                Some(entry.value.clone())
+            }
+            None => None,
+        }
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.data.get(key).is_some_and(|entry| !entry.is_expired(self.clock.now()))
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key, RemovalCause::Explicit).map(|entry| entry.value)
+    }
+
+    fn purge_expired(&mut self) -> usize {
+        let now = self.clock.now();
+        let mut purged = 0;
+        for key in self.timers.advance(now) {
+            let Some(entry) = self.data.get(&key) else {
+                continue;
+            };
+            if entry.is_expired(now) {
+                self.remove_entry(&key, RemovalCause::Expired);
+                purged += 1;
+            } else if let Some(deadline) = entry.deadline() {
+                // 空闲期限被读取延长了
+                self.timers.schedule(key, deadline);
+            }
+        }
+        self.next_expiry = self.timers.next_deadline();
+        purged
+    }
+
+    fn clear(&mut self) {
+        if self.listener.is_some() || self.stats.is_some() {
+            let now = self.clock.now();
+            for (key, entry) in &self.data {
+                // 还没来得及清理的过期条目按过期通知
+                let cause = if entry.is_expired(now) {
+                    RemovalCause::Expired
+                } else {
+                    RemovalCause::Cleared
+                };
+                if let Some(stats) = &mut self.stats {
+                    stats.record_removal(cause);
+                }
+                if let Some(listener) = &self.listener {
+                    listener(key, &entry.value, cause);
+                }
+            }
+        }
+        self.data.clear();
+        self.tag_index.clear();
+        self.policy.clear();
+        self.timers.clear();
+        self.next_expiry = None;
+        self.total_weight = 0;
+    }
+
+    fn for_each_entry(&self, visit: &mut dyn FnMut(&K, &V, EntryExpiry)) {
+        let now = self.clock.now();
+        let mut visit_live = |key: &K, entry: &CacheEntry<V>| {
+            if !entry.is_expired(now) {
+                visit(key, &entry.value, entry.expiry_at(now));
+            }
+        };
+        match self.policy.eviction_order() {
+            Some(keys) => {
+                for key in &keys {
+                    if let Some(entry) = self.data.get(key) {
+                        visit_live(key, entry);
+                    }
+                }
+            }
+            None => {
+                for (key, entry) in &self.data {
+                    visit_live(key, entry);
+                }
+            }
+        }
+    }
+
+    fn set_removal_listener(&mut self, listener: RemovalListener<K, V>) {
+        self.listener = Some(listener);
+    }
+
+    /// 未开启统计时返回全零的快照
+    fn stats(&self) -> CacheStats {
+        self.stats.unwrap_or_default()
+    }
+
+    fn set_stats_enabled(&mut self, enabled: bool) {
+        match (enabled, self.stats.is_some()) {
+            (true, false) => self.stats = Some(CacheStats::default()),
+            (false, true) => self.stats = None,
+            _ => {}
+        }
+    }
+
+    fn reset_stats(&mut self) {
+        if let Some(stats) = &mut self.stats {
+            *stats = CacheStats::default();
+        }
+    }
+
+    fn record_load(&mut self, success: bool, elapsed: Duration) {
+        if let Some(stats) = &mut self.stats {
+            stats.record_load(success, elapsed);
+        }
+    }
+
+    /// 只统计未过期的条目；确定没有条目过期时为 O(1)，`get` 和插入会清理到期的条目并更新这个判断
+    fn len(&self) -> usize {
+        let now = self.clock.now();
+        if self.may_have_expired(now) {
+            self.data.values().filter(|entry| !entry.is_expired(now)).count()
+        } else {
+            self.data.len()
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// [`PolicyCache::entry`] 返回的条目
+pub enum Entry<'a, K, V, P> {
+    Occupied(OccupiedEntry<'a, K, V, P>),
+    Vacant(VacantEntry<'a, K, V, P>),
+}
+
+/// 缓存中存在且未过期的条目
+pub struct OccupiedEntry<'a, K, V, P> {
+    cache: &'a mut PolicyCache<K, V, P>,
+    key: K,
+}
+
+/// 缓存中不存在（或已过期）的条目
+pub struct VacantEntry<'a, K, V, P> {
+    cache: &'a mut PolicyCache<K, V, P>,
+    key: K,
+}
+
+impl<'a, K, V, P> Entry<'a, K, V, P>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    P: EvictionPolicy<K>,
+{
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// 不存在时插入 `default`，返回缓存中值的可变引用
+    ///
+    /// 值没能放进缓存（容量为 0 或条目超重）时返回 None。
+    pub fn or_insert(self, default: V) -> Option<&'a mut V> {
+        self.or_insert_with(|| default)
+    }
+
+    /// 不存在时插入 `default()` 的结果，`default` 只在需要时调用
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> Option<&'a mut V> {
+        match self {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// 与 [`or_insert_with`](Self::or_insert_with) 相同，但 `default` 可以使用键
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> Option<&'a mut V> {
+        match self {
+            Entry::Occupied(entry) => Some(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// 条目存在时原地修改它的值
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, modify: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            modify(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, P> Entry<'a, K, V, P>
+where
+    K: Hash + Eq + Clone,
+    V: Clone + Default,
+    P: EvictionPolicy<K>,
+{
+    pub fn or_default(self) -> Option<&'a mut V> {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V, P> OccupiedEntry<'a, K, V, P>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    P: EvictionPolicy<K>,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn get(&self) -> &V {
+        &self.cache.data[&self.key].value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.cache.data.get_mut(&self.key).unwrap().value
+    }
+
+    /// 转换为与缓存借用同样长的可变引用
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.cache.data.get_mut(&self.key).unwrap().value
+    }
+
+    /// 替换值并返回旧值，过期时间和标签保持不变
+    ///
+    /// 与覆盖写入一样重新计算权重并在需要时淘汰其他条目：新值超重时返回
+    /// [`CacheError::EntryTooHeavy`]，原值保留；淘汰后仍放不下时返回 [`CacheError::CacheFull`]，条目已被删除。
+    pub fn insert(self, value: V) -> Result<V, CacheError> {
+        let cache = self.cache;
+        let weight = cache.weigher.as_ref().map_or(1, |weigher| weigher.weigh(&self.key, &value));
+        if let Some(max_weight) = cache.max_weight
+            && weight > max_weight
+        {
+            return Err(CacheError::EntryTooHeavy { weight, max_weight });
+        }
+        let entry = cache.data.get_mut(&self.key).unwrap();
+        cache.total_weight = cache.total_weight - entry.weight + weight;
+        entry.weight = weight;
+        let old = mem::replace(&mut entry.value, value);
+        if let Some(stats) = &mut cache.stats {
+            stats.inserts += 1;
+        }
+        cache.policy.on_access(&self.key);
+        cache.notify(&self.key, &old, RemovalCause::Replaced);
+        cache.make_room(0, 0);
+        if !cache.data.contains_key(&self.key) || cache.exceeds_capacity(0, 0) {
+            cache.remove_entry(&self.key, RemovalCause::Evicted);
+            return Err(CacheError::CacheFull);
+        }
+        Ok(old)
+    }
+
+    pub fn remove(self) -> V {
+        self.cache.remove_entry(&self.key, RemovalCause::Explicit).unwrap().value
+    }
+}
+
+impl<'a, K, V, P> VacantEntry<'a, K, V, P>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    P: EvictionPolicy<K>,
+{
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// 以默认的 TTL 和 TTI 插入，返回缓存中值的可变引用；值没能放进缓存时返回 None
+    pub fn insert(self, value: V) -> Option<&'a mut V> {
+        let ttl = self.cache.default_ttl;
+        self.insert_with_ttl(value, ttl)
+    }
+
+    /// 以指定的 TTL 插入
+    pub fn insert_with_ttl(self, value: V, ttl: Option<Duration>) -> Option<&'a mut V> {
+        let tti = self.cache.default_tti;
+        self.cache.insert_entry(self.key.clone(), value, ttl, tti, Vec::new()).ok()?;
+        self.cache.data.get_mut(&self.key).map(|entry| &mut entry.value)
+    }
+}
+
+fn index_tags<K: Hash + Eq + Clone>(tag_index: &mut HashMap<String, HashSet<K>>, key: &K, tags: &[String]) {
+    for tag in tags {
+        tag_index.entry(tag.clone()).or_default().insert(key.clone());
+    }
+}
+
+fn unindex_tags<K: Hash + Eq>(tag_index: &mut HashMap<String, HashSet<K>>, key: &K, tags: &[String]) {
+    for tag in tags {
+        if let Some(keys) = tag_index.get_mut(tag) {
+            keys.remove(key);
+            if keys.is_empty() {
+                tag_index.remove(tag);
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::lib::linkedlist::list_array::DoublyLinkedList;
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
+
+/// 2 位频次计数器的上限
+const MAX_FREQ: u8 = 3;
+
+/// S3-FIFO 缓存
+pub type S3FifoCache<K, V> = PolicyCache<K, V, S3FifoPolicy<K>>;
+
+impl<K, V> S3FifoCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(max_size: usize) -> Self {
+        PolicyCache::with_policy(S3FifoPolicy::new(max_size), max_size)
+    }
+}
+
+/// S3-FIFO 策略
+///
+/// 新键进入约占容量 10% 的小队列 S，其余空间为主队列 M，两者都是 FIFO。
+/// S 的队尾被访问过不止一次则移入 M，否则淘汰并把键记入幽灵队列 G；
+/// 插入时命中 G 的键直接进入 M。M 的队尾频次不为零时频次减一并重新入队，否则淘汰。
+/// 命中只增加键上的 2 位频次计数，不移动键。
+pub struct S3FifoPolicy<K> {
+    index: HashMap<K, S3FifoNode>,
+    small: DoublyLinkedList<K>,
+    main: DoublyLinkedList<K>,
+    ghost: DoublyLinkedList<K>,
+    ghost_index: HashMap<K, usize>,
+    small_size: usize,
+    ghost_size: usize,
+}
+
+struct S3FifoNode {
+    queue: Queue,
+    // 在所属队列中的下标
+    node: usize,
+    freq: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Queue {
+    Small,
+    Main,
+}
+
+impl<K> S3FifoPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        let small_size = (capacity / 10).max(1);
+        Self {
+            index: HashMap::new(),
+            small: DoublyLinkedList::new(),
+            main: DoublyLinkedList::new(),
+            ghost: DoublyLinkedList::new(),
+            ghost_index: HashMap::new(),
+            small_size,
+            ghost_size: capacity.saturating_sub(small_size).max(1),
+        }
+    }
+
+    fn queue_mut(&mut self, queue: Queue) -> &mut DoublyLinkedList<K> {
+        match queue {
+            Queue::Small => &mut self.small,
+            Queue::Main => &mut self.main,
+        }
+    }
+
+    /// 把键记入幽灵队列，幽灵队列的大小与主队列相同
+    fn remember(&mut self, key: K) {
+        let node = self.ghost.push_front(key.clone());
+        self.ghost_index.insert(key, node);
+        while self.ghost.len() > self.ghost_size {
+            if let Some(old) = self.ghost.pop_back() {
+                self.ghost_index.remove(&old);
+            }
+        }
+    }
+}
+
+impl<K> EvictionPolicy<K> for S3FifoPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn on_insert(&mut self, key: &K) {
+        let queue = match self.ghost_index.remove(key) {
+            Some(node) => {
+                self.ghost.remove(node);
+                Queue::Main
+            }
+            None => Queue::Small,
+        };
+        let node = self.queue_mut(queue).push_front(key.clone());
+        self.index.insert(key.clone(), S3FifoNode { queue, node, freq: 0 });
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if let Some(item) = self.index.get_mut(key) {
+            item.freq = (item.freq + 1).min(MAX_FREQ);
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some(item) = self.index.remove(key) {
+            self.queue_mut(item.queue).remove(item.node);
+        }
+    }
+
+    fn choose_victim(&mut self) -> Option<K> {
+        loop {
+            if self.small.len() >= self.small_size || self.main.is_empty() {
+                let key = self.small.pop_back()?;
+                let item = self.index.get_mut(&key)?;
+                if item.freq > 1 {
+                    item.freq = 0;
+                    item.queue = Queue::Main;
+                    item.node = self.main.push_front(key);
+                } else {
+                    self.index.remove(&key);
+                    self.remember(key.clone());
+                    return Some(key);
+                }
+            } else {
+                let key = self.main.pop_back()?;
+                let item = self.index.get_mut(&key)?;
+                if item.freq > 0 {
+                    item.freq -= 1;
+                    item.node = self.main.push_front(key);
+                } else {
+                    self.index.remove(&key);
+                    return Some(key);
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.small.clear();
+        self.main.clear();
+        self.ghost.clear();
+        self.ghost_index.clear();
+    }
+
+    /// 之后没有访问时依次淘汰的顺序，按 `choose_victim` 的规则在队列的副本上推演
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        let with_freq = |key: &'_ K| (key.clone(), self.index.get(key).map_or(0, |item| item.freq));
+        let mut small: VecDeque<(K, u8)> = self.small.iter().map(with_freq).collect();
+        let mut main: VecDeque<(K, u8)> = self.main.iter().map(with_freq).collect();
+        let mut keys = Vec::with_capacity(self.index.len());
+        loop {
+            if small.len() >= self.small_size || main.is_empty() {
+                let Some((key, freq)) = small.pop_back() else {
+                    break;
+                };
+                if freq > 1 {
+                    main.push_front((key, 0));
+                } else {
+                    keys.push(key);
+                }
+            } else if let Some((key, freq)) = main.pop_back() {
+                if freq > 0 {
+                    main.push_front((key, freq - 1));
+                } else {
+                    keys.push(key);
+                }
+            }
+        }
+        Some(keys)
+    }
+}
@@ -0,0 +1,126 @@
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::lib::linkedlist::list_array::DoublyLinkedList;
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
+
+/// SIEVE 缓存
+pub type SieveCache<K, V> = PolicyCache<K, V, SievePolicy<K>>;
+
+impl<K, V> SieveCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(max_size: usize) -> Self {
+        PolicyCache::with_policy(SievePolicy::new(), max_size)
+    }
+}
+
+/// SIEVE 策略
+///
+/// 键按插入顺序排成队列，头部最新。命中只设置访问位，不移动键。
+/// 淘汰指针从尾部向头部移动：访问位为真的清零并跳过，遇到为假的即淘汰；
+/// 指针位置在两次淘汰之间保留，到达头部后回到尾部。
+pub struct SievePolicy<K> {
+    // 键在 queue 中的下标和访问位
+    index: HashMap<K, (usize, bool)>,
+    queue: DoublyLinkedList<K>,
+    hand: Option<usize>,
+}
+
+impl<K> SievePolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            index: HashMap::new(),
+            queue: DoublyLinkedList::new(),
+            hand: None,
+        }
+    }
+}
+
+impl<K> Default for SievePolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> EvictionPolicy<K> for SievePolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn on_insert(&mut self, key: &K) {
+        let node = self.queue.push_front(key.clone());
+        self.index.insert(key.clone(), (node, false));
+    }
+
+    fn on_access(&mut self, key: &K) {
+        if let Some((_, visited)) = self.index.get_mut(key) {
+            *visited = true;
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some((node, _)) = self.index.remove(key) {
+            if self.hand == Some(node) {
+                self.hand = self.queue.prev(node);
+            }
+            self.queue.remove(node);
+        }
+    }
+
+    fn choose_victim(&mut self) -> Option<K> {
+        let mut cur = self.hand.or(self.queue.back());
+        while let Some(idx) = cur {
+            let key = self.queue.get(idx)?;
+            let (_, visited) = self.index.get_mut(key)?;
+            if *visited {
+                *visited = false;
+                cur = self.queue.prev(idx).or(self.queue.back());
+            } else {
+                self.hand = self.queue.prev(idx);
+                let key = self.queue.remove(idx)?;
+                self.index.remove(&key);
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.queue.clear();
+        self.hand = None;
+    }
+
+    /// 之后没有访问时指针依次淘汰的顺序：从指针处向头部转一圈，先是访问位为假的键，再是访问位为真的键
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        let mut nodes = Vec::with_capacity(self.queue.len());
+        let mut cur = self.queue.back();
+        while let Some(idx) = cur {
+            nodes.push(idx);
+            cur = self.queue.prev(idx);
+        }
+        if let Some(start) = self.hand.and_then(|hand| nodes.iter().position(|&idx| idx == hand)) {
+            nodes.rotate_left(start);
+        }
+        let mut unvisited = Vec::with_capacity(nodes.len());
+        let mut visited = Vec::new();
+        for key in nodes.into_iter().filter_map(|idx| self.queue.get(idx)) {
+            match self.index.get(key) {
+                Some((_, true)) => visited.push(key.clone()),
+                _ => unvisited.push(key.clone()),
+            }
+        }
+        unvisited.extend(visited);
+        Some(unvisited)
+    }
+}
@@ -0,0 +1,195 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// 计数矩阵的行数
+const DEPTH: usize = 4;
+/// 每行使用不同的种子，把同一个哈希值映射到不同的列
+const SEEDS: [u64; DEPTH] = [
+    0xc3a5_c85c_97cb_3127,
+    0xb492_b66f_be98_f273,
+    0x9ae1_6a3b_2f90_404f,
+    0xcbf2_9ce4_8422_2325,
+];
+/// 4 位计数器的最大值
+const MAX_COUNT: u64 = 15;
+/// 把每个 4 位计数器右移一位后清掉跨越计数器的进位
+const RESET_MASK: u64 = 0x7777_7777_7777_7777;
+
+fn hash_of<T: Hash + ?Sized>(item: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn spread(hash: u64, seed: u64) -> u64 {
+    let h = (hash ^ seed).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    h ^ (h >> 31)
+}
+
+/// 4 位计数器的 Count-Min Sketch，用于估计键的访问频次
+///
+/// 每个 u64 打包 16 个计数器，计数到 15 饱和。累计增加 `sample_size` 次后
+/// 所有计数器减半（老化），使频次估计能跟上访问模式的变化。
+pub struct CountMinSketch {
+    table: Vec<u64>,
+    // 每行的计数器个数，为 2 的幂
+    width: usize,
+    additions: usize,
+    sample_size: usize,
+}
+
+impl CountMinSketch {
+    /// `width` 为每行计数器个数（会向上取整为 2 的幂），`sample_size` 为老化周期
+    pub fn new(width: usize, sample_size: usize) -> Self {
+        let width = width.max(16).next_power_of_two();
+        Self {
+            table: vec![0; width * DEPTH / 16],
+            width,
+            additions: 0,
+            sample_size: sample_size.max(1),
+        }
+    }
+
+    /// 增加一次计数，返回本次是否触发了老化
+    pub fn increment<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let hash = hash_of(item);
+        let mut added = false;
+        for row in 0..DEPTH {
+            let (word, shift) = self.position(hash, row);
+            if (self.table[word] >> shift) & MAX_COUNT < MAX_COUNT {
+                self.table[word] += 1 << shift;
+                added = true;
+            }
+        }
+        if added {
+            self.additions += 1;
+            if self.additions >= self.sample_size {
+                self.halve();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// 估计频次，取各行计数的最小值
+    pub fn estimate<T: Hash + ?Sized>(&self, item: &T) -> u8 {
+        let hash = hash_of(item);
+        (0..DEPTH)
+            .map(|row| {
+                let (word, shift) = self.position(hash, row);
+                ((self.table[word] >> shift) & MAX_COUNT) as u8
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// 所有计数器减半
+    pub fn halve(&mut self) {
+        for word in &mut self.table {
+            *word = (*word >> 1) & RESET_MASK;
+        }
+        self.additions /= 2;
+    }
+
+    pub fn clear(&mut self) {
+        self.table.iter_mut().for_each(|word| *word = 0);
+        self.additions = 0;
+    }
+
+    fn position(&self, hash: u64, row: usize) -> (usize, u32) {
+        let col = spread(hash, SEEDS[row]) as usize & (self.width - 1);
+        let idx = row * self.width + col;
+        (idx / 16, ((idx % 16) * 4) as u32)
+    }
+}
+
+/// 门卫：一个布隆过滤器，拦截只出现过一次的键
+///
+/// 键第一次出现只记录在门卫中，再次出现才进入计数器，
+/// 避免大量一次性访问挤占 Count-Min Sketch 的计数空间。
+pub struct Doorkeeper {
+    bits: Vec<u64>,
+    // 位数，为 2 的幂
+    size: usize,
+}
+
+impl Doorkeeper {
+    /// `expected_items` 为老化周期内预计出现的不同键数量
+    pub fn new(expected_items: usize) -> Self {
+        let size = (expected_items.max(8) * 8).next_power_of_two();
+        Self {
+            bits: vec![0; size.div_ceil(64)],
+            size,
+        }
+    }
+
+    /// 记录一个键，返回它之前是否已经在门卫中
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let hash = hash_of(item);
+        let mut present = true;
+        for seed in &SEEDS[..3] {
+            let bit = spread(hash, *seed) as usize & (self.size - 1);
+            let mask = 1 << (bit % 64);
+            if self.bits[bit / 64] & mask == 0 {
+                present = false;
+                self.bits[bit / 64] |= mask;
+            }
+        }
+        present
+    }
+
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        let hash = hash_of(item);
+        SEEDS[..3].iter().all(|seed| {
+            let bit = spread(hash, *seed) as usize & (self.size - 1);
+            self.bits[bit / 64] & (1 << (bit % 64)) != 0
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+    }
+}
+
+/// TinyLFU 准入过滤器：门卫 + Count-Min Sketch
+///
+/// 淘汰时比较候选者和受害者的估计频次，只有候选者更热时才允许它替换受害者。
+pub struct TinyLfu {
+    sketch: CountMinSketch,
+    doorkeeper: Doorkeeper,
+}
+
+impl TinyLfu {
+    /// 按缓存容量配置，老化周期为容量的 10 倍
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            sketch: CountMinSketch::new(capacity, capacity * 10),
+            doorkeeper: Doorkeeper::new(capacity * 10),
+        }
+    }
+
+    /// 记录一次访问
+    pub fn record<T: Hash + ?Sized>(&mut self, item: &T) {
+        if !self.doorkeeper.insert(item) {
+            return;
+        }
+        if self.sketch.increment(item) {
+            self.doorkeeper.clear();
+        }
+    }
+
+    /// 估计频次（门卫中的一次也计入）
+    pub fn frequency<T: Hash + ?Sized>(&self, item: &T) -> u8 {
+        self.sketch.estimate(item) + self.doorkeeper.contains(item) as u8
+    }
+
+    /// 候选者是否应该替换受害者
+    pub fn admit<T: Hash + ?Sized>(&self, candidate: &T, victim: &T) -> bool {
+        self.frequency(candidate) > self.frequency(victim)
+    }
+
+    pub fn clear(&mut self) {
+        self.sketch.clear();
+        self.doorkeeper.clear();
+    }
+}
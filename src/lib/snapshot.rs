@@ -0,0 +1,210 @@
+use std::fmt;
+use std::fs;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::lib::cache::{Cache, EntryExpiry};
+use crate::lib::codec::{Decode, DecodeError, Encode, crc32, take};
+
+/// 快照文件开头的标识
+const MAGIC: &[u8; 8] = b"LCSNAPSH";
+/// 快照格式版本，格式变化时递增
+const VERSION: u16 = 1;
+
+// 条目标志位：哪些过期信息被写入
+const HAS_TTL: u8 = 1;
+const HAS_IDLE: u8 = 1 << 1;
+
+/// 快照读写错误
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    /// 文件开头不是快照标识
+    BadMagic,
+    /// 不支持的格式版本
+    UnsupportedVersion(u16),
+    /// 校验和不一致，文件已损坏
+    ChecksumMismatch,
+    Decode(DecodeError),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "快照读写失败: {}", error),
+            SnapshotError::BadMagic => write!(f, "不是快照文件"),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "不支持的快照版本 {}", version),
+            SnapshotError::ChecksumMismatch => write!(f, "快照校验和不一致"),
+            SnapshotError::Decode(error) => write!(f, "快照数据损坏: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+impl From<DecodeError> for SnapshotError {
+    fn from(error: DecodeError) -> Self {
+        SnapshotError::Decode(error)
+    }
+}
+
+/// 把缓存保存到文件和从文件恢复，对所有实现了 [`Cache`] 的类型自动可用
+///
+/// 文件格式（整数均为小端）：
+///
+/// ```text
+/// 标识 "LCSNAPSH" | 版本 u16 | 保存时刻（UNIX 秒 u64 + 纳秒 u32） | 条目数 u64
+/// 条目 * N：键 | 值 | 标志 u8 | [剩余 TTL 纳秒 u64] | [TTI 纳秒 u64 | 剩余空闲纳秒 u64]
+/// 以上所有字节的 CRC-32 u32
+/// ```
+///
+/// 过期时间按剩余时长保存，恢复时扣除进程停止期间经过的时间，期间过期的条目不会恢复；
+/// 带 TTI 的条目恢复后重新开始空闲计时。条目按淘汰先后写入，恢复后 `LruCache` 保持原有的新旧顺序。
+pub trait Snapshot<K, V>: Cache<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    /// 保存所有未过期的条目，返回保存的条目数
+    fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<usize, SnapshotError>
+    where
+        K: Encode,
+        V: Encode,
+    {
+        let mut body = Vec::new();
+        let mut count = 0u64;
+        self.for_each_entry(&mut |key, value, expiry| {
+            key.encode(&mut body);
+            value.encode(&mut body);
+            encode_expiry(&expiry, &mut body);
+            count += 1;
+        });
+
+        let saved_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mut out = Vec::with_capacity(body.len() + 34);
+        out.extend_from_slice(MAGIC);
+        VERSION.encode(&mut out);
+        saved_at.as_secs().encode(&mut out);
+        saved_at.subsec_nanos().encode(&mut out);
+        count.encode(&mut out);
+        out.extend_from_slice(&body);
+        crc32(&out).encode(&mut out);
+
+        // 先写临时文件再重命名，保存中途失败不会破坏已有的快照
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(&out)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(count as usize)
+    }
+
+    /// 把快照中的条目写入缓存，返回恢复的条目数；已过期的条目被跳过
+    fn load_snapshot(&mut self, path: impl AsRef<Path>) -> Result<usize, SnapshotError>
+    where
+        K: Decode,
+        V: Decode,
+    {
+        let bytes = fs::read(path)?;
+        if bytes.len() < MAGIC.len() + 4 {
+            return Err(SnapshotError::BadMagic);
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - 4);
+        if !content.starts_with(MAGIC) {
+            return Err(SnapshotError::BadMagic);
+        }
+        if u32::from_bytes(checksum)? != crc32(content) {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+
+        let mut input = &content[MAGIC.len()..];
+        let version = u16::decode(&mut input)?;
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let saved_at = UNIX_EPOCH + Duration::new(u64::decode(&mut input)?, u32::decode(&mut input)?);
+        // 系统时间被往回调过时按没有经过时间处理
+        let downtime = SystemTime::now().duration_since(saved_at).unwrap_or_default();
+        let count = u64::decode(&mut input)?;
+
+        let mut restored = 0;
+        for _ in 0..count {
+            let key = K::decode(&mut input)?;
+            let value = V::decode(&mut input)?;
+            let expiry = decode_expiry(&mut input)?;
+            let ttl = match expiry.ttl_remaining {
+                Some(remaining) if remaining <= downtime => continue,
+                Some(remaining) => Some(remaining - downtime),
+                None => None,
+            };
+            if expiry.idle_remaining.is_some_and(|remaining| remaining <= downtime) {
+                continue;
+            }
+            self.insert_with_expiry(key, value, ttl, expiry.idle);
+            restored += 1;
+        }
+        if !input.is_empty() {
+            return Err(DecodeError::TrailingBytes.into());
+        }
+        Ok(restored)
+    }
+}
+
+impl<K, V, C> Snapshot<K, V> for C
+where
+    K: Hash + Eq,
+    V: Clone,
+    C: Cache<K, V> + ?Sized,
+{
+}
+
+fn encode_duration(duration: Duration, out: &mut Vec<u8>) {
+    (duration.as_nanos().min(u64::MAX as u128) as u64).encode(out);
+}
+
+fn decode_duration(input: &mut &[u8]) -> Result<Duration, DecodeError> {
+    Ok(Duration::from_nanos(u64::decode(input)?))
+}
+
+pub(crate) fn encode_expiry(expiry: &EntryExpiry, out: &mut Vec<u8>) {
+    let mut flags = 0;
+    if expiry.ttl_remaining.is_some() {
+        flags |= HAS_TTL;
+    }
+    if expiry.idle.is_some() {
+        flags |= HAS_IDLE;
+    }
+    out.push(flags);
+    if let Some(remaining) = expiry.ttl_remaining {
+        encode_duration(remaining, out);
+    }
+    if let (Some(idle), Some(remaining)) = (expiry.idle, expiry.idle_remaining) {
+        encode_duration(idle, out);
+        encode_duration(remaining, out);
+    }
+}
+
+pub(crate) fn decode_expiry(input: &mut &[u8]) -> Result<EntryExpiry, DecodeError> {
+    let flags = take(input, 1)?[0];
+    if flags & !(HAS_TTL | HAS_IDLE) != 0 {
+        return Err(DecodeError::Invalid("未知的条目标志"));
+    }
+    let mut expiry = EntryExpiry::default();
+    if flags & HAS_TTL != 0 {
+        expiry.ttl_remaining = Some(decode_duration(input)?);
+    }
+    if flags & HAS_IDLE != 0 {
+        expiry.idle = Some(decode_duration(input)?);
+        expiry.idle_remaining = Some(decode_duration(input)?);
+    }
+    Ok(expiry)
+}
@@ -0,0 +1,115 @@
+use std::ops::Add;
+use std::time::Duration;
+
+use crate::lib::cache::RemovalCause;
+
+/// 缓存统计的快照
+///
+/// 统计默认关闭，开启后每次操作只是对普通整数加一，可以在生产环境常开。
+/// 只有 `get` 计入命中和未命中，`peek`、`contains_key` 不影响统计。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// 写入次数，包括覆盖写入
+    pub inserts: u64,
+    /// 超出容量被淘汰的条目数
+    pub evictions: u64,
+    /// 过期被删除的条目数
+    pub expirations: u64,
+    /// 调用 `remove` 删除的条目数
+    pub explicit_removals: u64,
+    /// 被新值覆盖的条目数
+    pub replacements: u64,
+    /// 调用 `clear` 清除的条目数
+    pub clears: u64,
+    pub load_successes: u64,
+    pub load_failures: u64,
+    /// 加载函数累计耗时
+    pub total_load_time: Duration,
+}
+
+impl CacheStats {
+    /// `get` 的总次数
+    pub fn request_count(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// 命中率，没有请求时为 0
+    pub fn hit_ratio(&self) -> f64 {
+        match self.request_count() {
+            0 => 0.0,
+            requests => self.hits as f64 / requests as f64,
+        }
+    }
+
+    pub fn miss_ratio(&self) -> f64 {
+        match self.request_count() {
+            0 => 0.0,
+            requests => self.misses as f64 / requests as f64,
+        }
+    }
+
+    pub fn load_count(&self) -> u64 {
+        self.load_successes + self.load_failures
+    }
+
+    /// 平均每次加载的耗时，没有加载时为 0
+    pub fn average_load_time(&self) -> Duration {
+        match self.load_count() {
+            0 => Duration::ZERO,
+            loads => self.total_load_time.div_f64(loads as f64),
+        }
+    }
+
+    /// 按原因统计的删除条目数
+    pub fn removal_count(&self, cause: RemovalCause) -> u64 {
+        match cause {
+            RemovalCause::Explicit => self.explicit_removals,
+            RemovalCause::Replaced => self.replacements,
+            RemovalCause::Expired => self.expirations,
+            RemovalCause::Evicted => self.evictions,
+            RemovalCause::Cleared => self.clears,
+        }
+    }
+
+    pub(crate) fn record_removal(&mut self, cause: RemovalCause) {
+        match cause {
+            RemovalCause::Explicit => self.explicit_removals += 1,
+            RemovalCause::Replaced => self.replacements += 1,
+            RemovalCause::Expired => self.expirations += 1,
+            RemovalCause::Evicted => self.evictions += 1,
+            RemovalCause::Cleared => self.clears += 1,
+        }
+    }
+
+    pub(crate) fn record_load(&mut self, success: bool, elapsed: Duration) {
+        if success {
+            self.load_successes += 1;
+        } else {
+            self.load_failures += 1;
+        }
+        self.total_load_time += elapsed;
+    }
+}
+
+/// 把多个缓存（例如并发缓存的各个分片）的统计相加
+impl Add for CacheStats {
+    type Output = CacheStats;
+
+    fn add(self, other: CacheStats) -> CacheStats {
+        CacheStats {
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+            inserts: self.inserts + other.inserts,
+            evictions: self.evictions + other.evictions,
+            expirations: self.expirations + other.expirations,
+            explicit_removals: self.explicit_removals + other.explicit_removals,
+            replacements: self.replacements + other.replacements,
+            clears: self.clears + other.clears,
+            load_successes: self.load_successes + other.load_successes,
+            load_failures: self.load_failures + other.load_failures,
+            total_load_time: self.total_load_time + other.total_load_time,
+        }
+    }
+}
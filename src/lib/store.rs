@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::lib::codec::{Decode, DecodeError, Encode};
+use crate::lib::concurrentcache::lock;
+
+/// 缓存背后较慢的持久存储
+///
+/// 方法都只需要 `&self`，写回模式下会在后台线程中调用。
+pub trait BackingStore<K, V>: Send + Sync {
+    /// 读取键对应的值，不存在时返回 None
+    fn load(&self, key: &K) -> Result<Option<V>, StoreError>;
+    fn store(&self, key: &K, value: &V) -> Result<(), StoreError>;
+    /// 删除键，键不存在时也返回成功
+    fn delete(&self, key: &K) -> Result<(), StoreError>;
+}
+
+/// 持久存储的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreError {
+    /// 读写失败
+    Io { kind: io::ErrorKind, message: String },
+    /// 存储中的数据无法解码
+    Decode(DecodeError),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io { message, .. } => write!(f, "存储读写失败: {}", message),
+            StoreError::Decode(error) => write!(f, "存储数据损坏: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+    fn from(error: io::Error) -> Self {
+        StoreError::Io {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<DecodeError> for StoreError {
+    fn from(error: DecodeError) -> Self {
+        StoreError::Decode(error)
+    }
+}
+
+/// 内存中的存储，用于测试和本地开发
+pub struct MemoryStore<K, V> {
+    data: Mutex<HashMap<K, V>>,
+}
+
+impl<K, V> MemoryStore<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            data: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        lock(&self.data).get(key).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.data).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        lock(&self.data).is_empty()
+    }
+}
+
+impl<K, V> Default for MemoryStore<K, V>
+where
+    K: Hash + Eq,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> BackingStore<K, V> for MemoryStore<K, V>
+where
+    K: Hash + Eq + Clone + Send,
+    V: Clone + Send,
+{
+    fn load(&self, key: &K) -> Result<Option<V>, StoreError> {
+        Ok(self.get(key))
+    }
+
+    fn store(&self, key: &K, value: &V) -> Result<(), StoreError> {
+        lock(&self.data).insert(key.clone(), value.clone());
+        Ok(())
+    }
+
+    fn delete(&self, key: &K) -> Result<(), StoreError> {
+        lock(&self.data).remove(key);
+        Ok(())
+    }
+}
+
+/// 基于目录的存储，每个键一个文件
+///
+/// 文件名是键编码后的十六进制，内容是值的编码。写入先写临时文件再重命名，
+/// 进程中途退出也不会留下写了一半的值。键编码后较长时可能超出文件系统对文件名长度的限制。
+pub struct FileStore<K, V> {
+    dir: PathBuf,
+    _marker: PhantomData<fn(K, V)>,
+}
+
+impl<K, V> FileStore<K, V> {
+    /// 打开目录，不存在时创建
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl<K: Encode, V> FileStore<K, V> {
+    fn path_of(&self, key: &K) -> PathBuf {
+        let name: String = key.to_bytes().iter().map(|byte| format!("{:02x}", byte)).collect();
+        self.dir.join(name)
+    }
+}
+
+impl<K, V> BackingStore<K, V> for FileStore<K, V>
+where
+    K: Encode,
+    V: Encode + Decode,
+{
+    fn load(&self, key: &K) -> Result<Option<V>, StoreError> {
+        match fs::read(self.path_of(key)) {
+            Ok(bytes) => Ok(Some(V::from_bytes(&bytes)?)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn store(&self, key: &K, value: &V) -> Result<(), StoreError> {
+        let path = self.path_of(key);
+        let tmp = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(&value.to_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &K) -> Result<(), StoreError> {
+        match fs::remove_file(self.path_of(key)) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,522 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hash;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::lib::cache::{Cache, CacheError, EntryExpiry, RemovalCause, RemovalListener};
+use crate::lib::clock::{Clock, MonotonicClock};
+use crate::lib::codec::{Decode, Encode, read_frame, write_frame};
+use crate::lib::concurrentcache::lock;
+use crate::lib::stats::CacheStats;
+
+/// 内存 + 磁盘两级缓存
+///
+/// 被内存层（任意 [`Cache`] 实现）淘汰的条目降级写入磁盘层，磁盘层命中的条目读出后提升回内存层，
+/// 一个键同一时刻只在其中一层。磁盘层是按段追加写入的日志文件，内存中只保存键到文件位置的索引；
+/// 超出字节容量时按先进先出整段回收最旧的段文件，其中还存活的条目随之淘汰。
+///
+/// 磁盘层只是内存层的溢出，打开时会清空目录中已有的段文件。
+/// 显式传入的 TTL 会随条目降级，内存层的默认 TTL 和 TTI 不会带到磁盘层。
+/// 统计只来自内存层，磁盘命中在内存层计为未命中。
+pub struct TieredCache<K, V> {
+    memory: Box<dyn Cache<K, V>>,
+    disk: DiskTier<K, V>,
+    // 内存层的删除事件，由内部监听器收集，在每次操作后处理
+    events: Arc<Mutex<Vec<(K, V, RemovalCause)>>>,
+    // 内存层中显式设置了 TTL 的键的过期时刻，降级时带到磁盘层
+    deadlines: HashMap<K, Instant>,
+    clock: Arc<dyn Clock>,
+    listener: Option<RemovalListener<K, V>>,
+}
+
+impl<K, V> TieredCache<K, V>
+where
+    K: Hash + Eq + Clone + Encode + Decode + Send + 'static,
+    V: Clone + Encode + Decode + Send + 'static,
+{
+    /// 用 `memory` 作为内存层，在目录 `dir` 中创建最多占用 `max_disk_bytes` 字节的磁盘层
+    ///
+    /// 会替换 `memory` 上已有的删除监听器，需要监听时调用 [`Cache::set_removal_listener`]。
+    pub fn new(mut memory: Box<dyn Cache<K, V>>, dir: impl AsRef<Path>, max_disk_bytes: u64) -> io::Result<Self> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        memory.set_removal_listener(Arc::new(move |key: &K, value: &V, cause| {
+            lock(&sink).push((key.clone(), value.clone(), cause));
+        }));
+        Ok(Self {
+            memory,
+            disk: DiskTier::create(dir.as_ref(), max_disk_bytes)?,
+            events,
+            deadlines: HashMap::new(),
+            clock: Arc::new(MonotonicClock),
+            listener: None,
+        })
+    }
+
+    /// 设置段文件大小，写满后开始新段，默认是磁盘容量的四分之一
+    pub fn with_segment_size(mut self, segment_size: u64) -> Self {
+        self.disk.segment_size = segment_size.clamp(1, self.disk.max_bytes.max(1));
+        self
+    }
+
+    /// 使用指定时钟计算磁盘层条目的过期，应与内存层使用同一个时钟
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn memory_len(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// 磁盘层中未过期的条目数
+    pub fn disk_len(&self) -> usize {
+        let now = self.clock.now();
+        self.disk.index.values().filter(|location| !location.is_expired(now)).count()
+    }
+
+    /// 段文件占用的总字节数，包括已删除或已提升条目留下的空间
+    pub fn disk_bytes(&self) -> u64 {
+        self.disk.bytes
+    }
+
+    /// 磁盘层当前的段文件数
+    pub fn segment_count(&self) -> usize {
+        self.disk.segments.len()
+    }
+
+    fn insert_into_memory(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        insert: impl FnOnce(&mut dyn Cache<K, V>, K, V) -> Result<(), CacheError>,
+    ) -> Result<(), CacheError> {
+        let result = insert(&mut *self.memory, key.clone(), value);
+        match ttl {
+            Some(ttl) if result.is_ok() => self.deadlines.insert(key.clone(), self.clock.now() + ttl),
+            _ => self.deadlines.remove(&key),
+        };
+        // 磁盘层的旧值被覆盖；新值没能写入时旧值同样过时，也要删除
+        if let Some(location) = self.disk.index.remove(&key) {
+            self.notify_disk(&key, &location, RemovalCause::Replaced);
+        }
+        self.drain_events();
+        result
+    }
+
+    /// 处理内存层的删除事件：淘汰的条目降级到磁盘层，其余转发给监听器
+    fn drain_events(&mut self) {
+        let events = mem::take(&mut *lock(&self.events));
+        for (key, value, cause) in events {
+            match cause {
+                RemovalCause::Evicted => {
+                    let expiry = self.deadlines.remove(&key);
+                    if expiry.is_some_and(|expiry| expiry <= self.clock.now()) {
+                        self.notify(&key, &value, RemovalCause::Expired);
+                        continue;
+                    }
+                    let reclaimed = self.disk.put(&key, &value, expiry, self.listener.is_some());
+                    match reclaimed {
+                        Ok(reclaimed) => {
+                            for (key, value) in reclaimed {
+                                self.notify(&key, &value, RemovalCause::Evicted);
+                            }
+                        }
+                        // 写不进磁盘层的条目直接淘汰
+                        Err(_) => self.notify(&key, &value, RemovalCause::Evicted),
+                    }
+                }
+                // 替换时新值的过期时刻已经记录
+                RemovalCause::Replaced => self.notify(&key, &value, cause),
+                _ => {
+                    self.deadlines.remove(&key);
+                    self.notify(&key, &value, cause);
+                }
+            }
+        }
+    }
+
+    fn notify(&self, key: &K, value: &V, cause: RemovalCause) {
+        if let Some(listener) = &self.listener {
+            listener(key, value, cause);
+        }
+    }
+
+    /// 通知磁盘层条目离开缓存，只在设置了监听器时读取值
+    fn notify_disk(&self, key: &K, location: &Location, cause: RemovalCause) {
+        if self.listener.is_some()
+            && let Some(value) = self.disk.read(key, location)
+        {
+            self.notify(key, &value, cause);
+        }
+    }
+
+    /// 在磁盘层查找未过期的条目
+    fn disk_lookup(&self, key: &K) -> Option<&Location> {
+        let now = self.clock.now();
+        self.disk.index.get(key).filter(|location| !location.is_expired(now))
+    }
+}
+
+impl<K, V> Cache<K, V> for TieredCache<K, V>
+where
+    K: Hash + Eq + Clone + Encode + Decode + Send + 'static,
+    V: Clone + Encode + Decode + Send + 'static,
+{
+    fn insert(&mut self, key: K, value: V) {
+        let _ = self.try_insert(key, value);
+    }
+
+    fn insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) {
+        let _ = self.try_insert_with_ttl(key, value, ttl);
+    }
+
+    fn insert_with_expiry(&mut self, key: K, value: V, ttl: Option<Duration>, tti: Option<Duration>) {
+        let _ = self.try_insert_with_expiry(key, value, ttl, tti);
+    }
+
+    fn try_insert(&mut self, key: K, value: V) -> Result<(), CacheError> {
+        self.insert_into_memory(key, value, None, |memory, key, value| memory.try_insert(key, value))
+    }
+
+    fn try_insert_with_ttl(&mut self, key: K, value: V, ttl: Option<Duration>) -> Result<(), CacheError> {
+        self.insert_into_memory(key, value, ttl, |memory, key, value| {
+            memory.try_insert_with_ttl(key, value, ttl)
+        })
+    }
+
+    fn try_insert_with_expiry(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+        tti: Option<Duration>,
+    ) -> Result<(), CacheError> {
+        self.insert_into_memory(key, value, ttl, |memory, key, value| {
+            memory.try_insert_with_expiry(key, value, ttl, tti)
+        })
+    }
+
+    /// 内存层未命中时查找磁盘层，命中的条目提升回内存层
+    fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.memory.get(key);
+        self.drain_events();
+        if value.is_some() {
+            return value;
+        }
+
+        let location = self.disk.index.remove(key)?;
+        let now = self.clock.now();
+        if location.is_expired(now) {
+            self.notify_disk(key, &location, RemovalCause::Expired);
+            return None;
+        }
+        let value = self.disk.read(key, &location)?;
+        let ttl = location.expiry.map(|expiry| expiry - now);
+        let promoted = self.insert_into_memory(key.clone(), value.clone(), ttl, |memory, key, value| match ttl {
+            Some(_) => memory.try_insert_with_ttl(key, value, ttl),
+            None => memory.try_insert(key, value),
+        });
+        // 放不进内存层的条目离开缓存，但仍然返回读到的值
+        if promoted.is_err() {
+            self.notify(key, &value, RemovalCause::Evicted);
+        }
+        Some(value)
+    }
+
+    /// 查看值但不提升
+    fn peek(&self, key: &K) -> Option<V> {
+        self.memory
+            .peek(key)
+            .or_else(|| self.disk_lookup(key).and_then(|location| self.disk.read(key, location)))
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.memory.contains_key(key) || self.disk_lookup(key).is_some()
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        if let Some(value) = self.memory.remove(key) {
+            self.drain_events();
+            return Some(value);
+        }
+        let location = self.disk.index.remove(key)?;
+        if location.is_expired(self.clock.now()) {
+            self.notify_disk(key, &location, RemovalCause::Expired);
+            return None;
+        }
+        let value = self.disk.read(key, &location)?;
+        self.notify(key, &value, RemovalCause::Explicit);
+        Some(value)
+    }
+
+    fn purge_expired(&mut self) -> usize {
+        let mut purged = self.memory.purge_expired();
+        self.drain_events();
+        let now = self.clock.now();
+        let expired: Vec<K> = self
+            .disk
+            .index
+            .iter()
+            .filter(|(_, location)| location.is_expired(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            if let Some(location) = self.disk.index.remove(&key) {
+                self.notify_disk(&key, &location, RemovalCause::Expired);
+                purged += 1;
+            }
+        }
+        purged
+    }
+
+    fn clear(&mut self) {
+        self.memory.clear();
+        self.drain_events();
+        if self.listener.is_some() {
+            let now = self.clock.now();
+            for (key, location) in self.disk.entries() {
+                let cause = if location.is_expired(now) { RemovalCause::Expired } else { RemovalCause::Cleared };
+                self.notify_disk(key, location, cause);
+            }
+        }
+        self.disk.clear();
+    }
+
+    /// 先访问磁盘层（从最早降级的开始），再访问内存层
+    fn for_each_entry(&self, visit: &mut dyn FnMut(&K, &V, EntryExpiry)) {
+        let now = self.clock.now();
+        for (key, location) in self.disk.entries() {
+            if location.is_expired(now) {
+                continue;
+            }
+            if let Some(value) = self.disk.read(key, location) {
+                let expiry = EntryExpiry {
+                    ttl_remaining: location.expiry.map(|expiry| expiry - now),
+                    ..EntryExpiry::default()
+                };
+                visit(key, &value, expiry);
+            }
+        }
+        self.memory.for_each_entry(visit);
+    }
+
+    fn set_removal_listener(&mut self, listener: RemovalListener<K, V>) {
+        self.listener = Some(listener);
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.memory.stats()
+    }
+
+    fn set_stats_enabled(&mut self, enabled: bool) {
+        self.memory.set_stats_enabled(enabled);
+    }
+
+    fn reset_stats(&mut self) {
+        self.memory.reset_stats();
+    }
+
+    fn record_load(&mut self, success: bool, elapsed: Duration) {
+        self.memory.record_load(success, elapsed);
+    }
+
+    fn len(&self) -> usize {
+        self.memory.len() + self.disk_len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 磁盘层：按段追加写入的记录和内存中的索引
+struct DiskTier<K, V> {
+    dir: PathBuf,
+    // 从旧到新，最后一段是正在写入的段
+    segments: VecDeque<Segment<K>>,
+    index: HashMap<K, Location>,
+    next_segment: u64,
+    segment_size: u64,
+    max_bytes: u64,
+    // 所有段文件的总长度
+    bytes: u64,
+    _value: PhantomData<V>,
+}
+
+struct Segment<K> {
+    id: u64,
+    file: File,
+    len: u64,
+    // 写入这一段的键和偏移，按写入顺序；之后被删除或改写的键不会移除，回收时对照索引跳过
+    keys: Vec<(u64, K)>,
+}
+
+/// 条目在段文件中的位置
+struct Location {
+    segment: u64,
+    offset: u64,
+    len: u64,
+    expiry: Option<Instant>,
+}
+
+impl Location {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expiry.is_some_and(|expiry| expiry <= now)
+    }
+}
+
+impl<K, V> DiskTier<K, V>
+where
+    K: Hash + Eq + Clone + Encode + Decode,
+    V: Encode + Decode,
+{
+    fn create(dir: &Path, max_bytes: u64) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "seg") {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            segments: VecDeque::new(),
+            index: HashMap::new(),
+            next_segment: 0,
+            segment_size: (max_bytes / 4).max(1),
+            max_bytes,
+            bytes: 0,
+            _value: PhantomData,
+        })
+    }
+
+    /// 追加一个条目，返回因超出容量而回收的条目（`read_values` 为 false 时不读取，返回空）
+    fn put(&mut self, key: &K, value: &V, expiry: Option<Instant>, read_values: bool) -> io::Result<Vec<(K, V)>> {
+        let mut record = Vec::new();
+        write_frame(&mut record, |payload| {
+            key.encode(payload);
+            value.encode(payload);
+        });
+        let len = record.len() as u64;
+        if len > self.max_bytes {
+            return Err(io::Error::other("条目超过磁盘层容量"));
+        }
+        if self
+            .segments
+            .back()
+            .is_none_or(|segment| segment.len > 0 && segment.len + len > self.segment_size)
+        {
+            self.open_segment()?;
+        }
+        let segment = self.segments.back_mut().unwrap();
+        segment.file.write_all(&record)?;
+        let location = Location {
+            segment: segment.id,
+            offset: segment.len,
+            len,
+            expiry,
+        };
+        segment.keys.push((segment.len, key.clone()));
+        segment.len += len;
+        self.bytes += len;
+        self.index.insert(key.clone(), location);
+
+        let mut reclaimed = Vec::new();
+        while self.bytes > self.max_bytes && self.segments.len() > 1 {
+            self.reclaim_oldest(read_values, &mut reclaimed);
+        }
+        Ok(reclaimed)
+    }
+
+    fn open_segment(&mut self) -> io::Result<()> {
+        let id = self.next_segment;
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(segment_path(&self.dir, id))?;
+        self.next_segment += 1;
+        self.segments.push_back(Segment {
+            id,
+            file,
+            len: 0,
+            keys: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// 回收最旧的段，其中还在索引里的条目一并淘汰
+    fn reclaim_oldest(&mut self, read_values: bool, reclaimed: &mut Vec<(K, V)>) {
+        let Some(mut segment) = self.segments.pop_front() else {
+            return;
+        };
+        for (offset, key) in mem::take(&mut segment.keys) {
+            let live = self
+                .index
+                .get(&key)
+                .is_some_and(|location| location.segment == segment.id && location.offset == offset);
+            if !live {
+                continue;
+            }
+            let location = self.index.remove(&key).unwrap();
+            if read_values && let Some(value) = Self::read_segment(&segment, &key, &location) {
+                reclaimed.push((key, value));
+            }
+        }
+        self.bytes -= segment.len;
+        let _ = fs::remove_file(segment_path(&self.dir, segment.id));
+    }
+
+    /// 读出条目的值，读取失败、校验失败或键不符时返回 None
+    fn read(&self, key: &K, location: &Location) -> Option<V> {
+        let segment = self.segments.iter().find(|segment| segment.id == location.segment)?;
+        Self::read_segment(segment, key, location)
+    }
+
+    fn read_segment(segment: &Segment<K>, key: &K, location: &Location) -> Option<V> {
+        let mut bytes = vec![0; location.len as usize];
+        let mut file = &segment.file;
+        file.seek(SeekFrom::Start(location.offset)).ok()?;
+        file.read_exact(&mut bytes).ok()?;
+        let mut payload = read_frame(&bytes)?;
+        if K::decode(&mut payload).ok()? != *key {
+            return None;
+        }
+        V::decode(&mut payload).ok()
+    }
+
+    /// 按写入顺序列出索引中的条目
+    fn entries(&self) -> Vec<(&K, &Location)> {
+        let mut entries: Vec<(&K, &Location)> = self.index.iter().collect();
+        entries.sort_by_key(|(_, location)| (location.segment, location.offset));
+        entries
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        for segment in self.segments.drain(..) {
+            let _ = fs::remove_file(segment_path(&self.dir, segment.id));
+        }
+        self.bytes = 0;
+    }
+}
+
+impl<K, V> Drop for DiskTier<K, V> {
+    /// 磁盘层只是溢出空间，关闭时删除段文件
+    fn drop(&mut self) {
+        for segment in self.segments.drain(..) {
+            let _ = fs::remove_file(segment_path(&self.dir, segment.id));
+        }
+    }
+}
+
+fn segment_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{:08}.seg", id))
+}
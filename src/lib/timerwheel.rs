@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Instant;
+
+use crate::lib::linkedlist::list_array::DoublyLinkedList;
+
+/// 各层的槽数：秒、分、时，最后一层存放一天以后才到期的键
+const SLOTS: [usize; 4] = [60, 60, 24, 1];
+/// 各层每个槽覆盖的秒数
+const SPANS: [u64; 4] = [1, 60, 3600, 86400];
+
+/// 分层时间轮，按到期时间索引键
+///
+/// 第 0 层每槽 1 秒，第 1 层每槽 1 分钟，第 2 层每槽 1 小时，超过一天的键放在溢出层。
+/// 时间推进到高层某槽的起点时，把该槽的键按剩余时间重新分配到低层（级联），
+/// 每个键最多级联三次，因此调度、取消和到期处理均摊都是 O(1)。
+/// 每个槽缓存其中最早的到期时间，查询最早到期时间只需查看各层的少数几个槽。
+pub struct TimerWheel<K> {
+    origin: Instant,
+    // 尚未处理完的最早一秒（相对 origin）
+    current: u64,
+    levels: Vec<Vec<Slot<K>>>,
+    index: HashMap<K, Location>,
+}
+
+/// 时间轮的一个槽
+struct Slot<K> {
+    entries: DoublyLinkedList<(K, Instant)>,
+    // 槽中最早的到期时间；最早的键被移出后置为 None，下次查询时重新计算
+    min: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Location {
+    level: usize,
+    slot: usize,
+    node: usize,
+}
+
+impl<K> TimerWheel<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        Self::starting_at(Instant::now())
+    }
+
+    /// 以指定时间作为第 0 秒
+    pub fn starting_at(origin: Instant) -> Self {
+        Self {
+            origin,
+            current: 0,
+            levels: SLOTS
+                .iter()
+                .map(|&slots| (0..slots).map(|_| Slot::new()).collect())
+                .collect(),
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// 登记键的到期时间，已登记的键会被重新调度
+    pub fn schedule(&mut self, key: K, deadline: Instant) {
+        self.cancel(&key);
+        self.place(key, deadline, self.current);
+    }
+
+    /// 取消键的到期登记
+    pub fn cancel(&mut self, key: &K) {
+        if let Some(loc) = self.index.remove(key) {
+            self.levels[loc.level][loc.slot].remove(loc.node);
+        }
+    }
+
+    /// 推进到 `now`，取出所有到期时间不晚于 `now` 的键
+    pub fn advance(&mut self, now: Instant) -> Vec<K> {
+        let target = self.tick_of(now);
+        let mut expired = Vec::new();
+        // 之前的整秒全部到期；没有键到期也不需要级联的秒直接跳过
+        while self.current < target {
+            self.cascade(self.current);
+            let slot = (self.current % SLOTS[0] as u64) as usize;
+            while let Some((key, _)) = self.levels[0][slot].pop_front() {
+                self.index.remove(&key);
+                expired.push(key);
+            }
+            self.current = self.next_event(self.current, target);
+        }
+        // 当前这一秒只取出已经到期的部分，剩余的留在槽中
+        if target == self.current {
+            self.cascade(target);
+            let slot = &mut self.levels[0][(target % SLOTS[0] as u64) as usize];
+            let mut cur = slot.entries.front();
+            while let Some(node) = cur {
+                cur = slot.entries.next(node);
+                let due = slot.entries.get(node).is_some_and(|(_, deadline)| *deadline <= now);
+                if due && let Some((key, _)) = slot.remove(node) {
+                    self.index.remove(&key);
+                    expired.push(key);
+                }
+            }
+        }
+        expired
+    }
+
+    /// 最早的到期时间
+    ///
+    /// 只查看各层从当前位置起的少数几个槽，槽的最小值有缓存，通常是 O(1)；
+    /// 只有槽中最早的键被取消或取出后，才在下次查询时重新扫描该槽。
+    pub fn next_deadline(&mut self) -> Option<Instant> {
+        (0..SLOTS.len()).filter_map(|level| self.level_min(level)).min()
+    }
+
+    /// 某一层中最早的到期时间
+    ///
+    /// 槽号是绝对时间取模，起始槽里可能混有绕了一圈、晚一整轮才到期的键，
+    /// 所以按绝对槽号从当前开始依次查找，直到某个槽的最小值确实落在它本轮覆盖的时间内。
+    fn level_min(&mut self, level: usize) -> Option<Instant> {
+        let len = SLOTS[level] as u64;
+        let start = self.current / SPANS[level];
+        let mut best: Option<Instant> = None;
+        for abs in start..start + len {
+            let Some(min) = self.levels[level][(abs % len) as usize].min() else {
+                continue;
+            };
+            let best = best.get_or_insert(min);
+            *best = (*best).min(min);
+            // 之后的槽覆盖的时间都更晚
+            if self.tick_of(min) / SPANS[level] <= abs {
+                break;
+            }
+        }
+        best
+    }
+
+    /// `after` 之后第一个需要处理的秒（有键到期或有非空的高层槽需要级联），不超过 `limit`
+    fn next_event(&self, after: u64, limit: u64) -> u64 {
+        if self.index.is_empty() {
+            return limit;
+        }
+        let mut next = limit;
+        // 第 0 层的键都在 after 之后的 60 秒内
+        for tick in after + 1..(after + SLOTS[0] as u64).min(next) {
+            if !self.levels[0][(tick % SLOTS[0] as u64) as usize].entries.is_empty() {
+                next = tick;
+                break;
+            }
+        }
+        // 高层槽在各自的起点级联
+        for level in 1..SLOTS.len() {
+            let slots = SLOTS[level] as u64;
+            let first = after / SPANS[level] + 1;
+            for abs in first..first + slots {
+                let tick = abs * SPANS[level];
+                if tick >= next {
+                    break;
+                }
+                if !self.levels[level][(abs % slots) as usize].entries.is_empty() {
+                    next = tick;
+                    break;
+                }
+            }
+        }
+        next
+    }
+
+    pub fn clear(&mut self) {
+        for slot in self.levels.iter_mut().flatten() {
+            slot.entries.clear();
+            slot.min = None;
+        }
+        self.index.clear();
+    }
+
+    fn tick_of(&self, time: Instant) -> u64 {
+        time.saturating_duration_since(self.origin).as_secs()
+    }
+
+    /// 按相对 `base` 的剩余时间选择层和槽，`base` 是尚未处理完的最早一秒
+    fn place(&mut self, key: K, deadline: Instant, base: u64) {
+        // 已经过去的时间统一放到 base，下次推进时取出
+        let tick = self.tick_of(deadline).max(base);
+        let delta = tick - base;
+        let level = if delta < SPANS[1] {
+            0
+        } else if delta < SPANS[2] {
+            1
+        } else if delta < SPANS[3] {
+            2
+        } else {
+            3
+        };
+        let slot = ((tick / SPANS[level]) % SLOTS[level] as u64) as usize;
+        let node = self.levels[level][slot].push(key.clone(), deadline);
+        self.index.insert(key, Location { level, slot, node });
+    }
+
+    /// 到达高层槽的起点时，把该槽的键重新分配到低层
+    fn cascade(&mut self, tick: u64) {
+        for level in (1..SLOTS.len()).rev() {
+            if !tick.is_multiple_of(SPANS[level]) {
+                continue;
+            }
+            let slot = ((tick / SPANS[level]) % SLOTS[level] as u64) as usize;
+            let entries: Vec<_> = std::iter::from_fn(|| self.levels[level][slot].pop_front()).collect();
+            for (key, deadline) in entries {
+                self.index.remove(&key);
+                self.place(key, deadline, tick);
+            }
+        }
+    }
+}
+
+impl<K> Default for TimerWheel<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Slot<K> {
+    fn new() -> Self {
+        Self {
+            entries: DoublyLinkedList::new(),
+            min: None,
+        }
+    }
+
+    fn push(&mut self, key: K, deadline: Instant) -> usize {
+        if self.entries.is_empty() {
+            self.min = Some(deadline);
+        } else if let Some(min) = &mut self.min {
+            *min = (*min).min(deadline);
+        }
+        self.entries.push_back((key, deadline))
+    }
+
+    fn remove(&mut self, node: usize) -> Option<(K, Instant)> {
+        let entry = self.entries.remove(node)?;
+        if self.min == Some(entry.1) {
+            self.min = None;
+        }
+        Some(entry)
+    }
+
+    fn pop_front(&mut self) -> Option<(K, Instant)> {
+        let node = self.entries.front()?;
+        self.remove(node)
+    }
+
+    /// 槽中最早的到期时间，缓存失效时重新扫描
+    fn min(&mut self) -> Option<Instant> {
+        if self.min.is_none() {
+            self.min = self.entries.iter().map(|(_, deadline)| *deadline).min();
+        }
+        self.min
+    }
+}
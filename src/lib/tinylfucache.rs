@@ -0,0 +1,209 @@
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::lib::linkedlist::list_array::DoublyLinkedList;
+use crate::lib::policy::EvictionPolicy;
+use crate::lib::policycache::PolicyCache;
+use crate::lib::sketch::TinyLfu;
+
+/// W-TinyLFU 缓存
+pub type TinyLfuCache<K, V> = PolicyCache<K, V, TinyLfuPolicy<K>>;
+
+impl<K, V> TinyLfuCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(max_size: usize) -> Self {
+        PolicyCache::with_policy(TinyLfuPolicy::new(max_size), max_size)
+    }
+}
+
+/// W-TinyLFU 策略
+///
+/// 新键先进入约占容量 1% 的窗口 LRU；需要淘汰时，窗口尾部的候选者
+/// 由 TinyLFU 与主区域的受害者比较频次，胜者留在主区域。
+/// 主区域是分段 LRU：试用段（probation）中的键再次命中后晋升到保护段（protected），
+/// 保护段约占主区域的 80%，溢出时降级回试用段。
+pub struct TinyLfuPolicy<K> {
+    index: HashMap<K, (Region, usize)>,
+    window: DoublyLinkedList<K>,
+    probation: DoublyLinkedList<K>,
+    protected: DoublyLinkedList<K>,
+    admittor: TinyLfu,
+    window_size: usize,
+    main_size: usize,
+    protected_size: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Region {
+    Window,
+    Probation,
+    Protected,
+}
+
+impl<K> TinyLfuPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        let window_size = (capacity / 100).max(1).min(capacity);
+        let main_size = capacity - window_size;
+        Self {
+            index: HashMap::new(),
+            window: DoublyLinkedList::new(),
+            probation: DoublyLinkedList::new(),
+            protected: DoublyLinkedList::new(),
+            admittor: TinyLfu::new(capacity),
+            window_size,
+            main_size,
+            protected_size: main_size * 8 / 10,
+        }
+    }
+
+    fn list_mut(&mut self, region: Region) -> &mut DoublyLinkedList<K> {
+        match region {
+            Region::Window => &mut self.window,
+            Region::Probation => &mut self.probation,
+            Region::Protected => &mut self.protected,
+        }
+    }
+
+    /// 把键挂到指定区域链表的头部
+    fn push(&mut self, key: &K, region: Region) {
+        let node = self.list_mut(region).push_front(key.clone());
+        self.index.insert(key.clone(), (region, node));
+    }
+
+    /// 从所在区域摘下并返回区域尾部的键
+    fn pop_back(&mut self, region: Region) -> Option<K> {
+        let key = self.list_mut(region).pop_back()?;
+        self.index.remove(&key);
+        Some(key)
+    }
+
+    /// 主区域的受害者：优先取试用段尾部
+    fn main_victim(&self) -> Option<(Region, K)> {
+        if let Some(node) = self.probation.back() {
+            return self.probation.get(node).map(|key| (Region::Probation, key.clone()));
+        }
+        let node = self.protected.back()?;
+        self.protected.get(node).map(|key| (Region::Protected, key.clone()))
+    }
+}
+
+impl<K> EvictionPolicy<K> for TinyLfuPolicy<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn on_insert(&mut self, key: &K) {
+        self.admittor.record(key);
+        self.push(key, Region::Window);
+        // 缓存未满时窗口溢出的键直接进入试用段
+        while self.window.len() > self.window_size {
+            match self.pop_back(Region::Window) {
+                Some(candidate) => self.push(&candidate, Region::Probation),
+                None => break,
+            }
+        }
+    }
+
+    /// 窗口内移到头部，试用段晋升到保护段
+    fn on_access(&mut self, key: &K) {
+        self.admittor.record(key);
+        let Some(&(region, node)) = self.index.get(key) else {
+            return;
+        };
+        match region {
+            Region::Window | Region::Protected => self.list_mut(region).move_to_front(node),
+            Region::Probation => {
+                self.probation.remove(node);
+                self.push(key, Region::Protected);
+                if self.protected.len() > self.protected_size
+                    && let Some(demoted) = self.pop_back(Region::Protected)
+                {
+                    self.push(&demoted, Region::Probation);
+                }
+            }
+        }
+    }
+
+    fn on_remove(&mut self, key: &K) {
+        if let Some((region, node)) = self.index.remove(key) {
+            self.list_mut(region).remove(node);
+        }
+    }
+
+    /// 窗口尾部的候选者与主区域的受害者竞争，败者被淘汰
+    fn choose_victim(&mut self) -> Option<K> {
+        if self.window.len() < self.window_size || self.main_size == 0 {
+            // 窗口未满（例如有键被显式删除）时直接从主区域淘汰
+            if let Some((region, _)) = self.main_victim() {
+                return self.pop_back(region);
+            }
+            return self.pop_back(Region::Window);
+        }
+        let Some(candidate_node) = self.window.back() else {
+            let (region, _) = self.main_victim()?;
+            return self.pop_back(region);
+        };
+        let candidate = self.window.get(candidate_node).cloned()?;
+        match self.main_victim() {
+            Some((region, victim)) if self.admittor.admit(&candidate, &victim) => {
+                self.pop_back(region);
+                self.window.remove(candidate_node);
+                self.push(&candidate, Region::Probation);
+                Some(victim)
+            }
+            _ => self.pop_back(Region::Window),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.window.clear();
+        self.probation.clear();
+        self.protected.clear();
+        self.admittor.clear();
+    }
+
+    /// 之后没有访问时依次淘汰的顺序，按 `choose_victim` 的规则在各区域的副本上推演
+    fn eviction_order(&self) -> Option<Vec<K>> {
+        let mut window: VecDeque<&K> = self.window.iter().collect();
+        let mut probation: VecDeque<&K> = self.probation.iter().collect();
+        let mut protected: VecDeque<&K> = self.protected.iter().collect();
+        let mut keys = Vec::with_capacity(self.index.len());
+        loop {
+            let main_victim = probation.back().or(protected.back()).copied();
+            let victim = if window.len() < self.window_size || self.main_size == 0 {
+                match main_victim {
+                    Some(_) => pop_main(&mut probation, &mut protected),
+                    None => window.pop_back(),
+                }
+            } else {
+                match (window.back().copied(), main_victim) {
+                    (None, _) => pop_main(&mut probation, &mut protected),
+                    (Some(candidate), Some(victim)) if self.admittor.admit(candidate, victim) => {
+                        pop_main(&mut probation, &mut protected);
+                        window.pop_back();
+                        probation.push_front(candidate);
+                        Some(victim)
+                    }
+                    _ => window.pop_back(),
+                }
+            };
+            match victim {
+                Some(key) => keys.push(key.clone()),
+                None => break,
+            }
+        }
+        Some(keys)
+    }
+}
+
+/// 推演淘汰顺序时取主区域的受害者：优先取试用段尾部
+fn pop_main<'a, K>(probation: &mut VecDeque<&'a K>, protected: &mut VecDeque<&'a K>) -> Option<&'a K> {
+    probation.pop_back().or_else(|| protected.pop_back())
+}
@@ -0,0 +1,25 @@
+/// 计算条目的权重，用于按权重限制缓存容量
+///
+/// 权重的含义由使用方决定，通常是值占用的字节数。任何 `Fn(&K, &V) -> u64` 闭包都实现了此 trait。
+pub trait Weigher<K, V>: Send + Sync {
+    fn weigh(&self, key: &K, value: &V) -> u64;
+}
+
+impl<K, V, F> Weigher<K, V> for F
+where
+    F: Fn(&K, &V) -> u64 + Send + Sync,
+{
+    fn weigh(&self, key: &K, value: &V) -> u64 {
+        self(key, value)
+    }
+}
+
+/// 每个条目的权重都是 1，此时权重上限等同于条目数上限
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnitWeigher;
+
+impl<K, V> Weigher<K, V> for UnitWeigher {
+    fn weigh(&self, _key: &K, _value: &V) -> u64 {
+        1
+    }
+}
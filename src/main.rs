@@ -1,5 +1,3 @@
-use std::time::Duration;
-
 use localcache::lib::cache::{new_cache, CacheType};
 
 fn main() {
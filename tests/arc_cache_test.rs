@@ -0,0 +1,139 @@
+use std::time::Duration;
+use localcache::lib::arccache::ArcCache;
+use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
+use localcache::lib::lrucache::LruCache;
+
+#[test]
+fn test_arc_cache_insert_and_get() {
+    let mut cache: ArcCache<String, String> = ArcCache::new(100);
+
+    // 测试插入和获取
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 测试不存在的键
+    assert_eq!(cache.get(&"nonexistent".to_string()), None);
+}
+
+#[test]
+fn test_arc_cache_with_size_limit() {
+    let mut cache: ArcCache<i32, i32> = ArcCache::new(3);
+
+    for i in 0..10 {
+        cache.insert(i, i);
+        assert!(cache.len() <= 3);
+    }
+    assert_eq!(cache.len(), 3);
+    // 只访问过一次的条目按 LRU 顺序淘汰
+    assert!(cache.contains_key(&9));
+    assert!(!cache.contains_key(&0));
+}
+
+#[test]
+fn test_arc_cache_frequent_entries_survive_new_inserts() {
+    let mut cache: ArcCache<i32, i32> = ArcCache::new(2);
+
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+    // 1 被访问两次，进入 T2
+    assert_eq!(cache.get(&1), Some(1));
+
+    // 之后只访问一次的新条目在 T1 中互相淘汰
+    cache.insert(3, 3);
+    cache.insert(4, 4);
+    cache.insert(5, 5);
+    assert_eq!(cache.get(&1), Some(1));
+    assert!(cache.contains_key(&5));
+    assert!(!cache.contains_key(&2));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn test_arc_cache_scan_resistant_compared_with_lru() {
+    let capacity = 10;
+    let mut arc: ArcCache<i32, i32> = ArcCache::new(capacity);
+    let mut lru: LruCache<i32, i32> = LruCache::new(capacity);
+    let hot: Vec<i32> = (0..5).collect();
+
+    // 热点数据被访问两次
+    for &key in &hot {
+        arc.insert(key, key);
+        lru.insert(key, key);
+        arc.get(&key);
+        lru.get(&key);
+    }
+
+    // 一次性的顺序扫描
+    for key in 1000..1100 {
+        arc.insert(key, key);
+        lru.insert(key, key);
+    }
+
+    let arc_hits = hot.iter().filter(|key| arc.get(key).is_some()).count();
+    let lru_hits = hot.iter().filter(|key| lru.get(key).is_some()).count();
+    assert_eq!(arc_hits, hot.len());
+    assert_eq!(lru_hits, 0);
+}
+
+#[test]
+fn test_arc_cache_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache: ArcCache<String, String> = ArcCache::new(100).with_clock(clock.clone());
+
+    // 插入带TTL的值
+    cache.insert_with_ttl(
+        "key1".to_string(),
+        "value1".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+
+    // 应该能获取到值
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+
+    // 应该获取不到值（已过期）
+    assert_eq!(cache.get(&"key1".to_string()), None);
+}
+
+#[test]
+fn test_arc_cache_remove_and_clear() {
+    let mut cache: ArcCache<String, i32> = ArcCache::new(2);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    assert_eq!(cache.remove(&"key1".to_string()), Some(1));
+    assert_eq!(cache.get(&"key1".to_string()), None);
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+// 测试 B2 幽灵命中时先减小 p 再淘汰，|T1| == p 时从 T1 淘汰
+#[test]
+fn test_arc_cache_victim_after_b2_hit() {
+    let mut cache: ArcCache<&str, i32> = ArcCache::new(3);
+    cache.insert("e", 1);
+    cache.insert("d", 2);
+    cache.insert("f", 3);
+    // d 进入 T2
+    cache.get(&"d");
+    // e 从 T1 淘汰进入 B1
+    cache.insert("b", 4);
+    // B1 命中：p = 1，f 从 T1 淘汰进入 B1，e 进入 T2
+    cache.insert("e", 1);
+    assert!(!cache.contains_key(&"f"));
+    // B1 命中：p = 2，|T1| = 1 不超过 p，d 从 T2 淘汰进入 B2
+    cache.insert("f", 3);
+    assert!(!cache.contains_key(&"d"));
+
+    // B2 命中：p 先减为 1，此时 |T1| == p，淘汰 T1 中的 b 而不是 T2 中的 e
+    cache.insert("d", 2);
+    assert!(!cache.contains_key(&"b"));
+    assert!(cache.contains_key(&"e"));
+    assert!(cache.contains_key(&"f"));
+    assert!(cache.contains_key(&"d"));
+}
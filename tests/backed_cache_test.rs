@@ -0,0 +1,243 @@
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use localcache::lib::backedcache::{BackedCache, WriteMode};
+use localcache::lib::cache::{Cache, CacheError};
+use localcache::lib::lrucache::LruCache;
+use localcache::lib::store::{BackingStore, FileStore, MemoryStore, StoreError};
+
+/// 总是写入失败的存储
+struct BrokenStore;
+
+impl BackingStore<String, String> for BrokenStore {
+    fn load(&self, _key: &String) -> Result<Option<String>, StoreError> {
+        Ok(None)
+    }
+
+    fn store(&self, _key: &String, _value: &String) -> Result<(), StoreError> {
+        Err(std::io::Error::other("磁盘已满").into())
+    }
+
+    fn delete(&self, _key: &String) -> Result<(), StoreError> {
+        Err(std::io::Error::other("磁盘已满").into())
+    }
+}
+
+/// 写入前先通知测试，再等测试放行的存储
+struct GatedStore {
+    inner: MemoryStore<String, String>,
+    entered: Mutex<Sender<()>>,
+    gate: Arc<Mutex<()>>,
+}
+
+impl BackingStore<String, String> for GatedStore {
+    fn load(&self, key: &String) -> Result<Option<String>, StoreError> {
+        self.inner.load(key)
+    }
+
+    fn store(&self, key: &String, value: &String) -> Result<(), StoreError> {
+        let _ = self.entered.lock().unwrap().send(());
+        let _gate = self.gate.lock().unwrap();
+        self.inner.store(key, value)
+    }
+
+    fn delete(&self, key: &String) -> Result<(), StoreError> {
+        self.inner.delete(key)
+    }
+}
+
+fn write_behind(flush_interval: Duration, max_batch: usize) -> WriteMode {
+    WriteMode::WriteBehind { flush_interval, max_batch }
+}
+
+// 测试写穿模式下写入和删除同步到存储
+#[test]
+fn test_write_through_insert_and_remove() {
+    let store = Arc::new(MemoryStore::new());
+    let mut cache = BackedCache::new(Box::new(LruCache::new(10)), store.clone(), WriteMode::WriteThrough);
+
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(store.get(&"key1".to_string()), Some("value1".to_string()));
+
+    assert_eq!(cache.remove(&"key1".to_string()), Some("value1".to_string()));
+    assert_eq!(store.get(&"key1".to_string()), None);
+}
+
+// 测试未命中时从存储加载并放入缓存
+#[test]
+fn test_load_on_miss() {
+    let store = Arc::new(MemoryStore::new());
+    store.store(&"key1".to_string(), &"value1".to_string()).unwrap();
+    let mut cache = BackedCache::new(Box::new(LruCache::new(10)), store.clone(), WriteMode::WriteThrough);
+
+    assert!(!cache.contains_key(&"key1".to_string()));
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+    assert!(cache.contains_key(&"key1".to_string()));
+    assert_eq!(cache.try_get(&"missing".to_string()), Ok(None));
+}
+
+// 测试写穿失败时返回错误，缓存保持不变
+#[test]
+fn test_write_through_failure() {
+    let mut cache: BackedCache<String, String> =
+        BackedCache::new(Box::new(LruCache::new(10)), Arc::new(BrokenStore), WriteMode::WriteThrough);
+
+    let result = cache.try_insert("key1".to_string(), "value1".to_string());
+    assert!(matches!(result, Err(CacheError::Store(StoreError::Io { .. }))));
+    assert!(cache.is_empty());
+
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert!(cache.is_empty());
+}
+
+// 测试存储已写入但缓存拒绝新值时，缓存中的旧值被删除，之后从存储读到新值
+#[test]
+fn test_cache_rejects_written_value() {
+    for mode in [WriteMode::WriteThrough, write_behind(Duration::from_secs(3600), 100)] {
+        let store = Arc::new(MemoryStore::new());
+        let weighted = LruCache::new(10).with_max_weight(10, |_: &String, value: &String| value.len() as u64);
+        let mut cache = BackedCache::new(Box::new(weighted), store.clone(), mode);
+        cache.insert("key1".to_string(), "old".to_string());
+
+        let heavy = "x".repeat(50);
+        let result = cache.try_insert("key1".to_string(), heavy.clone());
+        assert_eq!(result, Err(CacheError::EntryTooHeavy { weight: 50, max_weight: 10 }));
+        assert!(!cache.contains_key(&"key1".to_string()));
+        assert_eq!(cache.get(&"key1".to_string()), Some(heavy.clone()));
+        cache.flush().unwrap();
+        assert_eq!(store.get(&"key1".to_string()), Some(heavy));
+    }
+}
+
+// 测试写回模式先只写缓存，flush 后才写入存储
+#[test]
+fn test_write_behind_flush() {
+    let store = Arc::new(MemoryStore::new());
+    let mut cache = BackedCache::new(
+        Box::new(LruCache::new(10)),
+        store.clone(),
+        write_behind(Duration::from_secs(3600), 1000),
+    );
+
+    cache.insert("key1".to_string(), "value1".to_string());
+    cache.insert("key2".to_string(), "value2".to_string());
+    cache.insert("key1".to_string(), "value1b".to_string());
+    assert_eq!(cache.pending_writes(), 2);
+    assert!(store.is_empty());
+
+    cache.flush().unwrap();
+    assert_eq!(cache.pending_writes(), 0);
+    assert_eq!(store.get(&"key1".to_string()), Some("value1b".to_string()));
+    assert_eq!(store.len(), 2);
+}
+
+// 测试写回模式下删除后即使存储里还有旧值也读不到
+#[test]
+fn test_write_behind_pending_delete_hides_stored_value() {
+    let store = Arc::new(MemoryStore::new());
+    store.store(&"key1".to_string(), &"old".to_string()).unwrap();
+    let mut cache = BackedCache::new(
+        Box::new(LruCache::new(10)),
+        store.clone(),
+        write_behind(Duration::from_secs(3600), 1000),
+    );
+
+    cache.remove(&"key1".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), None);
+    cache.flush().unwrap();
+    assert!(store.is_empty());
+}
+
+// 测试释放缓存时写完剩余的修改
+#[test]
+fn test_write_behind_flush_on_drop() {
+    let store = Arc::new(MemoryStore::new());
+    {
+        let mut cache = BackedCache::new(
+            Box::new(LruCache::new(10)),
+            store.clone(),
+            write_behind(Duration::from_secs(3600), 1000),
+        );
+        for i in 0..5 {
+            cache.insert(i, i * 10);
+        }
+        assert!(store.is_empty());
+    }
+    assert_eq!(store.len(), 5);
+    assert_eq!(store.get(&3), Some(30));
+}
+
+// 测试后台线程定时写入和攒够一批时写入
+#[test]
+fn test_write_behind_background_flush() {
+    let wait_until = |done: &dyn Fn() -> bool| {
+        let start = Instant::now();
+        while !done() && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        done()
+    };
+
+    let store = Arc::new(MemoryStore::new());
+    let mut cache = BackedCache::new(Box::new(LruCache::new(10)), store.clone(), write_behind(Duration::from_millis(20), 1000));
+    cache.insert(1, 1);
+    assert!(wait_until(&|| store.len() == 1));
+
+    let store = Arc::new(MemoryStore::new());
+    let mut cache = BackedCache::new(Box::new(LruCache::new(10)), store.clone(), write_behind(Duration::from_secs(3600), 3));
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+    cache.insert(3, 3);
+    assert!(wait_until(&|| store.len() == 3));
+}
+
+// 测试文件存储的读写和删除
+#[test]
+fn test_file_store_roundtrip() {
+    let dir = std::env::temp_dir().join(format!("localcache-file-store-{}", std::process::id()));
+    {
+        let store = Arc::new(FileStore::open(&dir).unwrap());
+        let mut cache = BackedCache::new(Box::new(LruCache::new(10)), store.clone(), WriteMode::WriteThrough);
+        cache.insert("key1".to_string(), "value1".to_string());
+        cache.insert("键2".to_string(), "值2".to_string());
+        cache.remove(&"key1".to_string());
+    }
+
+    let store: Arc<FileStore<String, String>> = Arc::new(FileStore::open(&dir).unwrap());
+    assert_eq!(store.load(&"键2".to_string()), Ok(Some("值2".to_string())));
+    assert_eq!(store.load(&"key1".to_string()), Ok(None));
+
+    let mut cache = BackedCache::new(Box::new(LruCache::new(10)), store, WriteMode::WriteThrough);
+    assert_eq!(cache.get(&"键2".to_string()), Some("值2".to_string()));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+// 测试正在写入存储的一批修改在写完之前仍然对读取可见
+#[test]
+fn test_write_behind_in_flight_batch_visible() {
+    let (entered, entered_rx) = mpsc::channel();
+    let gate = Arc::new(Mutex::new(()));
+    let inner = MemoryStore::new();
+    inner.store(&"key1".to_string(), &"old".to_string()).unwrap();
+    let store = Arc::new(GatedStore {
+        inner,
+        entered: Mutex::new(entered),
+        gate: Arc::clone(&gate),
+    });
+    let mut cache = BackedCache::new(Box::new(LruCache::new(1)), store, write_behind(Duration::from_secs(3600), 2));
+    // 在缓存之后持有，断言失败时先于缓存释放，不会卡住后台线程的退出
+    let held = gate.lock().unwrap();
+
+    cache.insert("key1".to_string(), "new".to_string());
+    // 攒够一批触发后台写入，同时把 key1 挤出缓存
+    cache.insert("key2".to_string(), "value2".to_string());
+    assert!(!cache.contains_key(&"key1".to_string()));
+    entered_rx.recv().unwrap();
+
+    // 后台线程卡在写入中，读取不能越过这批修改读到存储里的旧值
+    assert_eq!(cache.pending_writes(), 2);
+    assert_eq!(cache.get(&"key1".to_string()), Some("new".to_string()));
+    drop(held);
+    cache.flush().unwrap();
+    assert_eq!(cache.pending_writes(), 0);
+}
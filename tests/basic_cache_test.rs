@@ -1,6 +1,7 @@
 use std::time::Duration;
 use localcache::lib::basiccache::BasicCache;
 use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
 
 #[test]
 fn test_basic_cache_insert_and_get() {
@@ -46,7 +47,8 @@ fn test_basic_cache_clear() {
 
 #[test]
 fn test_basic_cache_with_ttl() {
-    let mut cache: BasicCache<String, String> = BasicCache::new();
+    let clock = MockClock::new();
+    let mut cache: BasicCache<String, String> = BasicCache::new().with_clock(clock.clone());
 
     // 插入带TTL的值
     cache.insert_with_ttl(
@@ -58,9 +60,111 @@ fn test_basic_cache_with_ttl() {
     // 应该能获取到值
     assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
 
-    // 等待过期
-    std::thread::sleep(Duration::from_millis(150));
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
 
     // 应该获取不到值（已过期）
     assert_eq!(cache.get(&"key1".to_string()), None);
 }
+
+#[test]
+fn test_basic_cache_peek_and_contains_key() {
+    let mut cache: BasicCache<String, String> = BasicCache::new();
+
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(cache.peek(&"key1".to_string()), Some("value1".to_string()));
+    assert!(cache.contains_key(&"key1".to_string()));
+
+    assert_eq!(cache.peek(&"nonexistent".to_string()), None);
+    assert!(!cache.contains_key(&"nonexistent".to_string()));
+}
+
+#[test]
+fn test_basic_cache_purge_expired() {
+    let clock = MockClock::new();
+    let mut cache: BasicCache<String, String> = BasicCache::new().with_clock(clock.clone());
+
+    // 插入带TTL的值和不过期的值
+    cache.insert_with_ttl(
+        "key1".to_string(),
+        "value1".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+    cache.insert_with_ttl(
+        "key2".to_string(),
+        "value2".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+    cache.insert("key3".to_string(), "value3".to_string());
+    assert_eq!(cache.len(), 3);
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+
+    // len 只统计未过期的条目
+    assert_eq!(cache.len(), 1);
+    assert!(!cache.is_empty());
+
+    // 主动清理会删除两个过期条目
+    assert_eq!(cache.purge_expired(), 2);
+    assert_eq!(cache.purge_expired(), 0);
+    assert_eq!(cache.get(&"key3".to_string()), Some("value3".to_string()));
+}
+
+#[test]
+fn test_basic_cache_purges_expired_on_insert() {
+    let clock = MockClock::new();
+    let mut cache: BasicCache<i32, i32> = BasicCache::new().with_clock(clock.clone());
+
+    for i in 0..100 {
+        cache.insert_with_ttl(i, i, Some(Duration::from_millis(100)));
+    }
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+    assert!(cache.is_empty());
+
+    // 后续插入会顺带清理掉过期条目
+    for i in 100..300 {
+        cache.insert(i, i);
+    }
+    assert_eq!(cache.purge_expired(), 0);
+    assert_eq!(cache.len(), 200);
+}
+
+// 测试大量长 TTL 条目存在时，短 TTL 的写入顺带清理过期条目不会扫描全部条目
+#[test]
+fn test_basic_cache_expiry_cost_with_long_ttl_keys() {
+    let clock = MockClock::new();
+    let mut cache: BasicCache<u64, u64> = BasicCache::new().with_clock(clock.clone());
+    for i in 0..100_000 {
+        cache.insert_with_ttl(i, i, Some(Duration::from_secs(2 * 86400)));
+    }
+
+    let start = std::time::Instant::now();
+    for i in 0..20_000 {
+        cache.insert_with_ttl(1_000_000 + i % 1000, i, Some(Duration::from_secs(1)));
+        clock.advance(Duration::from_millis(10));
+    }
+    assert!(start.elapsed() < Duration::from_secs(2), "{:?}", start.elapsed());
+    assert_eq!(cache.len(), 100_000 + 100);
+}
+
+// 测试条目过期后读取会顺带清理，之后统计数量不再扫描全部条目
+#[test]
+fn test_basic_cache_len_after_expiry_is_cheap() {
+    let clock = MockClock::new();
+    let mut cache: BasicCache<u64, u64> = BasicCache::new().with_clock(clock.clone());
+    for i in 0..100_000 {
+        cache.insert(i, i);
+    }
+    cache.insert_with_ttl(u64::MAX, 0, Some(Duration::from_secs(1)));
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(cache.get(&0), Some(0));
+
+    let start = std::time::Instant::now();
+    for _ in 0..10_000 {
+        assert_eq!(cache.len(), 100_000);
+    }
+    assert!(start.elapsed() < Duration::from_secs(1), "{:?}", start.elapsed());
+}
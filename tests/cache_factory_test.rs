@@ -1,4 +1,5 @@
-use localcache::lib::cache::{CacheType, new_cache};
+use localcache::lib::cache::{CacheType, new_cache, new_cache_with_clock};
+use localcache::lib::clock::MockClock;
 use std::time::Duration;
 
 #[test]
@@ -24,3 +25,82 @@ fn test_new_cache_lru() {
     assert_eq!(removed_value, Some(100));
     assert_eq!(cache.get(&"lru_key".to_string()), None);
 }
+
+#[test]
+fn test_new_cache_lru_get_promotes_through_trait_object() {
+    let mut cache = new_cache::<String, i32>(CacheType::Lru(2));
+
+    cache.insert("a".to_string(), 1);
+    cache.insert("b".to_string(), 2);
+    assert_eq!(cache.get(&"a".to_string()), Some(1));
+    cache.insert("c".to_string(), 3);
+
+    assert!(cache.contains_key(&"a".to_string()));
+    assert!(!cache.contains_key(&"b".to_string()));
+    assert_eq!(cache.peek(&"c".to_string()), Some(3));
+}
+
+#[test]
+fn test_new_cache_lfu() {
+    let mut cache = new_cache::<String, i32>(CacheType::Lfu(100));
+
+    cache.insert("lfu_key".to_string(), 100);
+    assert_eq!(cache.get(&"lfu_key".to_string()), Some(100));
+
+    let removed_value = cache.remove(&"lfu_key".to_string());
+    assert_eq!(removed_value, Some(100));
+    assert_eq!(cache.get(&"lfu_key".to_string()), None);
+}
+
+#[test]
+fn test_new_cache_arc() {
+    let mut cache = new_cache::<String, i32>(CacheType::Arc(100));
+
+    cache.insert("arc_key".to_string(), 100);
+    assert_eq!(cache.get(&"arc_key".to_string()), Some(100));
+
+    let removed_value = cache.remove(&"arc_key".to_string());
+    assert_eq!(removed_value, Some(100));
+    assert_eq!(cache.get(&"arc_key".to_string()), None);
+}
+
+#[test]
+fn test_new_cache_tinylfu() {
+    let mut cache = new_cache::<String, i32>(CacheType::TinyLfu(100));
+
+    cache.insert("tinylfu_key".to_string(), 100);
+    assert_eq!(cache.get(&"tinylfu_key".to_string()), Some(100));
+
+    let removed_value = cache.remove(&"tinylfu_key".to_string());
+    assert_eq!(removed_value, Some(100));
+    assert_eq!(cache.get(&"tinylfu_key".to_string()), None);
+}
+
+#[test]
+fn test_new_cache_clock_and_sieve() {
+    for cache_type in [CacheType::Clock(100), CacheType::Sieve(100)] {
+        let mut cache = new_cache::<String, i32>(cache_type);
+
+        cache.insert("key".to_string(), 100);
+        assert_eq!(cache.get(&"key".to_string()), Some(100));
+
+        let removed_value = cache.remove(&"key".to_string());
+        assert_eq!(removed_value, Some(100));
+        assert_eq!(cache.get(&"key".to_string()), None);
+    }
+}
+
+#[test]
+fn test_new_cache_s3fifo_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache = new_cache_with_clock::<String, i32, _>(CacheType::S3Fifo(100), clock.clone());
+
+    cache.insert("key".to_string(), 100);
+    cache.insert_with_ttl("ttl_key".to_string(), 200, Some(Duration::from_millis(50)));
+    assert_eq!(cache.get(&"key".to_string()), Some(100));
+    assert_eq!(cache.get(&"ttl_key".to_string()), Some(200));
+
+    clock.advance(Duration::from_millis(80));
+    assert_eq!(cache.get(&"ttl_key".to_string()), None);
+    assert_eq!(cache.get(&"key".to_string()), Some(100));
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
+use localcache::lib::clockcache::ClockCache;
+
+#[test]
+fn test_clock_cache_insert_and_get() {
+    let mut cache: ClockCache<String, String> = ClockCache::new(100);
+
+    // 测试插入和获取
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 测试不存在的键
+    assert_eq!(cache.get(&"nonexistent".to_string()), None);
+}
+
+#[test]
+fn test_clock_cache_with_size_limit() {
+    let mut cache: ClockCache<i32, i32> = ClockCache::new(3);
+
+    for i in 0..10 {
+        cache.insert(i, i);
+        assert!(cache.len() <= 3);
+    }
+    // 没有命中时按插入顺序淘汰
+    assert_eq!(cache.len(), 3);
+    assert!(cache.contains_key(&7));
+    assert!(cache.contains_key(&8));
+    assert!(cache.contains_key(&9));
+}
+
+#[test]
+fn test_clock_cache_visited_entry_gets_second_chance() {
+    let mut cache: ClockCache<String, i32> = ClockCache::new(2);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    // key1 被访问过，淘汰时跳过它
+    assert_eq!(cache.get(&"key1".to_string()), Some(1));
+    cache.insert("key3".to_string(), 3);
+
+    assert!(cache.contains_key(&"key1".to_string()));
+    assert!(!cache.contains_key(&"key2".to_string()));
+    assert!(cache.contains_key(&"key3".to_string()));
+
+    // 访问位已被清零，下一次淘汰轮到 key1
+    cache.insert("key4".to_string(), 4);
+    assert!(!cache.contains_key(&"key1".to_string()));
+    assert!(cache.contains_key(&"key3".to_string()));
+}
+
+#[test]
+fn test_clock_cache_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache: ClockCache<String, String> = ClockCache::new(100).with_clock(clock.clone());
+
+    // 插入带TTL的值
+    cache.insert_with_ttl(
+        "key1".to_string(),
+        "value1".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+
+    // 应该能获取到值
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+
+    // 应该获取不到值（已过期）
+    assert_eq!(cache.get(&"key1".to_string()), None);
+}
+
+#[test]
+fn test_clock_cache_remove_and_clear() {
+    let mut cache: ClockCache<String, i32> = ClockCache::new(2);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    assert_eq!(cache.remove(&"key1".to_string()), Some(1));
+    assert_eq!(cache.get(&"key1".to_string()), None);
+
+    // 删除腾出的位置可以直接复用
+    cache.insert("key3".to_string(), 3);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains_key(&"key2".to_string()));
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
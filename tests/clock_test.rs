@@ -0,0 +1,59 @@
+use std::time::Duration;
+use localcache::lib::cache::Cache;
+use localcache::lib::clock::{Clock, MockClock, MonotonicClock};
+use localcache::lib::lrucache::LruCache;
+
+// 测试模拟时钟只在手动推进时前进，克隆共享同一时间
+#[test]
+fn test_mock_clock_advance() {
+    let clock = MockClock::new();
+    let shared = clock.clone();
+    let start = clock.now();
+    assert_eq!(clock.now(), start);
+
+    shared.advance(Duration::from_secs(5));
+    assert_eq!(clock.now(), start + Duration::from_secs(5));
+    assert_eq!(clock.elapsed(), Duration::from_secs(5));
+}
+
+// 测试单调时钟不会倒退
+#[test]
+fn test_monotonic_clock() {
+    let clock = MonotonicClock;
+    let first = clock.now();
+    assert!(clock.now() >= first);
+}
+
+// 测试 TTL 恰好到期的边界：到期时刻仍可读，之后才过期
+#[test]
+fn test_ttl_boundary_with_mock_clock() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_clock(clock.clone());
+    cache.insert_with_ttl(1, 1, Some(Duration::from_secs(10)));
+
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(cache.peek(&1), Some(1));
+
+    clock.advance(Duration::from_millis(1));
+    assert_eq!(cache.peek(&1), None);
+    assert!(!cache.contains_key(&1));
+    assert_eq!(cache.purge_expired(), 1);
+    assert!(cache.is_empty());
+}
+
+// 测试很长的 TTL 也无需真实等待
+#[test]
+fn test_long_ttl_with_mock_clock() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_clock(clock.clone());
+    cache.insert_with_ttl(1, 1, Some(Duration::from_secs(2 * 86400)));
+    cache.insert_with_ttl(2, 2, Some(Duration::from_secs(3600)));
+
+    clock.advance(Duration::from_secs(3601));
+    assert_eq!(cache.purge_expired(), 1);
+    assert_eq!(cache.get(&1), Some(1));
+
+    clock.advance(Duration::from_secs(2 * 86400));
+    assert_eq!(cache.purge_expired(), 1);
+    assert!(cache.is_empty());
+}
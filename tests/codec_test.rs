@@ -0,0 +1,45 @@
+use localcache::lib::codec::{Decode, DecodeError, Encode, crc32};
+
+// 测试整数、布尔值和字符串的编码往返
+#[test]
+fn test_codec_roundtrip() {
+    assert_eq!(u64::from_bytes(&12345u64.to_bytes()), Ok(12345));
+    assert_eq!(i32::from_bytes(&(-7i32).to_bytes()), Ok(-7));
+    assert_eq!(usize::from_bytes(&usize::MAX.to_bytes()), Ok(usize::MAX));
+    assert_eq!(bool::from_bytes(&true.to_bytes()), Ok(true));
+    assert_eq!(String::from_bytes(&"缓存".to_string().to_bytes()), Ok("缓存".to_string()));
+    assert_eq!(Vec::<u8>::from_bytes(&vec![1u8, 2, 3].to_bytes()), Ok(vec![1, 2, 3]));
+}
+
+// 测试多个值依次编码后可以依次解码
+#[test]
+fn test_codec_sequence() {
+    let mut out = Vec::new();
+    "key".to_string().encode(&mut out);
+    42u32.encode(&mut out);
+    "value".to_string().encode(&mut out);
+
+    let mut input = out.as_slice();
+    assert_eq!(String::decode(&mut input), Ok("key".to_string()));
+    assert_eq!(u32::decode(&mut input), Ok(42));
+    assert_eq!(String::decode(&mut input), Ok("value".to_string()));
+    assert!(input.is_empty());
+}
+
+// 测试损坏的数据返回错误
+#[test]
+fn test_codec_errors() {
+    assert_eq!(u64::from_bytes(&[1, 2, 3]), Err(DecodeError::UnexpectedEnd));
+    assert_eq!(u8::from_bytes(&[1, 2]), Err(DecodeError::TrailingBytes));
+    assert!(matches!(bool::from_bytes(&[2]), Err(DecodeError::Invalid(_))));
+    assert!(matches!(String::from_bytes(&[2, 0, 0, 0, 0xff, 0xfe]), Err(DecodeError::Invalid(_))));
+    assert_eq!(String::from_bytes(&[10, 0, 0, 0, b'a']), Err(DecodeError::UnexpectedEnd));
+}
+
+// 测试 CRC-32 与标准校验值一致
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    assert_eq!(crc32(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use localcache::lib::cache::CacheType;
+use localcache::lib::clock::MockClock;
+use localcache::lib::concurrentcache::ConcurrentCache;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+// 测试基本操作
+#[test]
+fn test_concurrent_cache_basic_operations() {
+    assert_send_sync::<ConcurrentCache<String, String>>();
+
+    let cache = ConcurrentCache::new(CacheType::Lru(100), 4);
+    assert_eq!(cache.shard_count(), 4);
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+    assert_eq!(cache.peek(&"key1".to_string()), Some("value1".to_string()));
+    assert!(cache.contains_key(&"key1".to_string()));
+    assert_eq!(cache.len(), 1);
+
+    assert_eq!(cache.remove(&"key1".to_string()), Some("value1".to_string()));
+    assert!(cache.is_empty());
+}
+
+// 测试容量分给各分片后整体不超过总容量（向上取整）
+#[test]
+fn test_concurrent_cache_capacity_split() {
+    let cache = ConcurrentCache::new(CacheType::Lru(64), 8);
+    for i in 0..1000 {
+        cache.insert(i, i);
+    }
+    assert!(cache.len() <= 64);
+    assert!(!cache.is_empty());
+
+    let cache = ConcurrentCache::new(CacheType::Sieve(0), 4);
+    cache.insert(1, 1);
+    assert!(cache.is_empty());
+}
+
+// 测试共享模拟时钟的过期
+#[test]
+fn test_concurrent_cache_ttl() {
+    let clock = MockClock::new();
+    let cache = ConcurrentCache::with_clock(CacheType::Basic, 4, clock.clone());
+    for i in 0..10 {
+        cache.insert_with_ttl(i, i, Some(Duration::from_secs(10)));
+    }
+    cache.insert(100, 100);
+
+    clock.advance(Duration::from_secs(11));
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.purge_expired(), 10);
+    assert_eq!(cache.get(&100), Some(100));
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+// 压力测试：多个读线程和写线程同时访问，写入的值始终与键一致
+#[test]
+fn test_concurrent_cache_stress_readers_and_writers() {
+    for cache_type in [
+        CacheType::Basic,
+        CacheType::Lru(500),
+        CacheType::Lfu(500),
+        CacheType::Arc(500),
+        CacheType::TinyLfu(500),
+        CacheType::Clock(500),
+        CacheType::Sieve(500),
+        CacheType::S3Fifo(500),
+    ] {
+        let cache = Arc::new(ConcurrentCache::new(cache_type, 16));
+        let mut handles = Vec::new();
+        for t in 0..8u64 {
+            let cache = Arc::clone(&cache);
+            handles.push(thread::spawn(move || {
+                for i in 0..5000u64 {
+                    let key = (i * 7 + t) % 2000;
+                    cache.insert(key, key * 10);
+                    if i % 10 == 0 {
+                        cache.remove(&((key + 1) % 2000));
+                    }
+                }
+            }));
+        }
+        for t in 0..8u64 {
+            let cache = Arc::clone(&cache);
+            handles.push(thread::spawn(move || {
+                for i in 0..5000u64 {
+                    let key = (i * 13 + t) % 2000;
+                    if let Some(value) = cache.get(&key) {
+                        assert_eq!(value, key * 10);
+                    }
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(cache.len() <= 2000);
+    }
+}
+
+// 测试多个线程写入不同的键后全部可读
+#[test]
+fn test_concurrent_cache_disjoint_writers() {
+    let cache = ConcurrentCache::new(CacheType::Basic, 8);
+    thread::scope(|scope| {
+        for t in 0..8 {
+            let cache = &cache;
+            scope.spawn(move || {
+                for i in 0..1000 {
+                    cache.insert(t * 1000 + i, i);
+                }
+            });
+        }
+    });
+    assert_eq!(cache.len(), 8000);
+    for t in 0..8 {
+        for i in 0..1000 {
+            assert_eq!(cache.get(&(t * 1000 + i)), Some(i));
+        }
+    }
+}
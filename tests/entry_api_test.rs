@@ -0,0 +1,151 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use localcache::lib::basiccache::BasicCache;
+use localcache::lib::cache::{Cache, CacheError, RemovalCause};
+use localcache::lib::clock::MockClock;
+use localcache::lib::lrucache::LruCache;
+use localcache::lib::policycache::Entry;
+
+// 测试不存在时插入、存在时原地修改
+#[test]
+fn test_entry_or_insert_and_modify() {
+    let mut cache: BasicCache<&str, Vec<i32>> = BasicCache::new();
+    cache.entry("a").or_insert_with(|| vec![1]);
+    cache.entry("a").or_insert_with(|| unreachable!()).unwrap().push(2);
+    assert_eq!(cache.peek(&"a"), Some(vec![1, 2]));
+
+    cache.entry("a").and_modify(|v| v.push(3)).or_insert(vec![0]);
+    cache.entry("b").and_modify(|v| v.push(3)).or_insert(vec![0]);
+    assert_eq!(cache.peek(&"a"), Some(vec![1, 2, 3]));
+    assert_eq!(cache.peek(&"b"), Some(vec![0]));
+
+    let len = cache.entry("key").or_insert_with_key(|key| vec![key.len() as i32]).unwrap().len();
+    assert_eq!(len, 1);
+    assert_eq!(cache.entry("c").or_default().unwrap().len(), 0);
+    assert_eq!(cache.len(), 4);
+}
+
+// 测试已过期的条目视为空位
+#[test]
+fn test_entry_expired_is_vacant() {
+    let clock = MockClock::new();
+    let mut cache: BasicCache<i32, i32> = BasicCache::new().with_clock(clock.clone());
+    cache.insert_with_ttl(1, 10, Some(Duration::from_secs(1)));
+    assert!(matches!(cache.entry(1), Entry::Occupied(_)));
+
+    clock.advance(Duration::from_secs(2));
+    assert!(matches!(cache.entry(1), Entry::Vacant(_)));
+    *cache.entry(1).and_modify(|v| *v += 1).or_insert(0).unwrap() += 5;
+    assert_eq!(cache.peek(&1), Some(5));
+
+    // 通过空位插入的条目带有指定的 TTL
+    match cache.entry(2) {
+        Entry::Vacant(entry) => assert_eq!(entry.insert_with_ttl(20, Some(Duration::from_secs(1))), Some(&mut 20)),
+        Entry::Occupied(_) => unreachable!(),
+    }
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(cache.peek(&2), None);
+}
+
+// 测试占用条目的读取、替换和删除
+#[test]
+fn test_occupied_entry() {
+    let removed = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&removed);
+    let mut cache: BasicCache<i32, String> = BasicCache::new()
+        .with_removal_listener(Arc::new(move |key: &i32, value: &String, cause| {
+            sink.lock().unwrap().push((*key, value.clone(), cause))
+        }))
+        .with_stats();
+    cache.insert(1, "one".to_string());
+
+    let Entry::Occupied(mut entry) = cache.entry(1) else {
+        panic!("键 1 应该存在");
+    };
+    assert_eq!(entry.key(), &1);
+    assert_eq!(entry.get(), "one");
+    entry.get_mut().push('!');
+    assert_eq!(entry.insert("uno".to_string()), Ok("one!".to_string()));
+    let Entry::Occupied(entry) = cache.entry(1) else {
+        panic!("键 1 应该存在");
+    };
+    assert_eq!(entry.remove(), "uno");
+    assert!(cache.is_empty());
+
+    assert_eq!(
+        *removed.lock().unwrap(),
+        vec![
+            (1, "one!".to_string(), RemovalCause::Replaced),
+            (1, "uno".to_string(), RemovalCause::Explicit),
+        ]
+    );
+    let Entry::Vacant(entry) = cache.entry(2) else {
+        panic!("键 2 不应该存在");
+    };
+    assert_eq!(entry.into_key(), 2);
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses), (2, 1));
+    assert_eq!(stats.inserts, 2);
+}
+
+// 测试条目 API 参与淘汰顺序，容量为 0 时不插入
+#[test]
+fn test_entry_counts_as_access() {
+    let mut cache: LruCache<i32, i32> = LruCache::new(2);
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+    cache.entry(1).and_modify(|v| *v += 10);
+    cache.insert(3, 3);
+    assert_eq!(cache.peek(&1), Some(11));
+    assert!(!cache.contains_key(&2));
+
+    let mut empty: LruCache<i32, i32> = LruCache::new(0);
+    assert_eq!(empty.entry(1).or_insert(1), None);
+    assert!(empty.is_empty());
+}
+
+// 测试通过条目替换值时重新计算权重，超重时拒绝，变重时淘汰其他条目
+#[test]
+fn test_entry_insert_reweighs() {
+    let mut cache: LruCache<i32, String> =
+        LruCache::new(10).with_max_weight(10, |_: &i32, value: &String| value.len() as u64);
+    cache.insert(1, "aaa".to_string());
+    cache.insert(2, "bbb".to_string());
+    cache.insert(3, "ccc".to_string());
+
+    let Entry::Occupied(entry) = cache.entry(1) else {
+        panic!("键 1 应该存在");
+    };
+    assert_eq!(
+        entry.insert("x".repeat(50)),
+        Err(CacheError::EntryTooHeavy {
+            weight: 50,
+            max_weight: 10
+        })
+    );
+    assert_eq!(cache.peek(&1), Some("aaa".to_string()));
+    assert_eq!(cache.weight(), 9);
+
+    let Entry::Occupied(entry) = cache.entry(1) else {
+        panic!("键 1 应该存在");
+    };
+    assert_eq!(entry.insert("x".repeat(6)), Ok("aaa".to_string()));
+    assert_eq!(cache.weight(), 9);
+    assert!(!cache.contains_key(&2));
+    assert_eq!(cache.peek(&3), Some("ccc".to_string()));
+}
+
+// 测试不淘汰的缓存通过条目写入放不下的值时返回 CacheFull 并删除条目
+#[test]
+fn test_entry_insert_cache_full() {
+    let mut cache: BasicCache<i32, String> =
+        BasicCache::new().with_max_weight(10, |_: &i32, value: &String| value.len() as u64);
+    cache.insert(1, "aaa".to_string());
+    cache.insert(2, "bbb".to_string());
+    let Entry::Occupied(entry) = cache.entry(1) else {
+        panic!("键 1 应该存在");
+    };
+    assert_eq!(entry.insert("x".repeat(8)), Err(CacheError::CacheFull));
+    assert!(!cache.contains_key(&1));
+    assert_eq!(cache.weight(), 3);
+}
@@ -0,0 +1,155 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use localcache::lib::basiccache::BasicCache;
+use localcache::lib::cache::{Cache, CacheExt, CacheType, RemovalCause, new_cache_with_clock};
+use localcache::lib::clock::MockClock;
+use localcache::lib::arccache::ArcPolicy;
+use localcache::lib::clockcache::ClockPolicy;
+use localcache::lib::lfucache::{LfuCache, LfuPolicy};
+use localcache::lib::lrucache::{LruCache, LruPolicy};
+use localcache::lib::policy::EvictionPolicy;
+use localcache::lib::s3fifocache::S3FifoPolicy;
+use localcache::lib::sievecache::SievePolicy;
+use localcache::lib::tinylfucache::TinyLfuPolicy;
+
+// 测试 LRU 按从最近使用到最久未使用的顺序遍历
+#[test]
+fn test_iter_follows_lru_order() {
+    let mut cache: LruCache<i32, &str> = LruCache::new(3);
+    cache.insert(1, "one");
+    cache.insert(2, "two");
+    cache.insert(3, "three");
+    cache.get(&1);
+
+    assert_eq!(cache.iter().collect::<Vec<_>>(), vec![(1, "one"), (3, "three"), (2, "two")]);
+    assert_eq!(cache.keys().collect::<Vec<_>>(), vec![1, 3, 2]);
+    assert_eq!(cache.values().collect::<Vec<_>>(), vec!["one", "three", "two"]);
+    // 遍历不影响淘汰顺序，最后一个最先被淘汰
+    cache.insert(4, "four");
+    assert_eq!(cache.keys().collect::<Vec<_>>(), vec![4, 1, 3]);
+}
+
+// 测试遍历跳过过期条目，也适用于装箱的缓存
+#[test]
+fn test_iter_skips_expired() {
+    let clock = MockClock::new();
+    let mut cache = new_cache_with_clock::<i32, i32, _>(CacheType::Lru(10), clock.clone());
+    cache.insert(1, 1);
+    cache.insert_with_ttl(2, 2, Some(Duration::from_secs(1)));
+    cache.insert(3, 3);
+    assert_eq!(cache.keys().count(), 3);
+
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(cache.keys().collect::<Vec<_>>(), vec![3, 1]);
+}
+
+// 测试按条件保留条目，删除的条目按显式删除通知
+#[test]
+fn test_retain() {
+    let removed = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&removed);
+    let mut cache: BasicCache<i32, i32> = BasicCache::new()
+        .with_removal_listener(Arc::new(move |key: &i32, _: &i32, cause| sink.lock().unwrap().push((*key, cause))));
+    for i in 0..10 {
+        cache.insert(i, i * i);
+    }
+    assert_eq!(cache.retain(|key, value| key % 2 == 0 && *value < 50), 6);
+    let mut keys: Vec<i32> = cache.keys().collect();
+    keys.sort();
+    assert_eq!(keys, vec![0, 2, 4, 6]);
+    assert_eq!(removed.lock().unwrap().len(), 6);
+    assert!(removed.lock().unwrap().iter().all(|(_, cause)| *cause == RemovalCause::Explicit));
+}
+
+// 测试取出所有未过期的条目并清空缓存
+#[test]
+fn test_drain() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_clock(clock.clone());
+    cache.insert(1, 10);
+    cache.insert_with_ttl(2, 20, Some(Duration::from_secs(1)));
+    cache.insert(3, 30);
+    clock.advance(Duration::from_secs(2));
+
+    assert_eq!(cache.drain().collect::<Vec<_>>(), vec![(3, 30), (1, 10)]);
+    assert!(cache.is_empty());
+    assert_eq!(cache.iter().count(), 0);
+    assert_eq!(cache.drain().count(), 0);
+}
+
+// 测试 LFU 按频次从高到低遍历，同一频次内从最近使用到最久未使用
+#[test]
+fn test_iter_follows_lfu_order() {
+    let mut cache: LfuCache<i32, i32> = LfuCache::new(4);
+    for key in 1..=4 {
+        cache.insert(key, key);
+    }
+    cache.get(&1);
+    cache.get(&1);
+    cache.get(&3);
+    cache.get(&2);
+    assert_eq!(cache.keys().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+}
+
+/// 用伪随机的插入、访问和删除驱动策略，检查给出的淘汰顺序与之后依次淘汰的顺序一致
+fn check_eviction_order<P: EvictionPolicy<u64>>(mut policy: P, capacity: usize) {
+    let mut seed: u64 = 7;
+    let mut live = Vec::new();
+    for _ in 0..5000 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let key = (seed >> 33) % (capacity as u64 * 3);
+        if let Some(pos) = live.iter().position(|&live_key| live_key == key) {
+            if (seed >> 20).is_multiple_of(8) {
+                policy.on_remove(&key);
+                live.swap_remove(pos);
+            } else {
+                policy.on_access(&key);
+            }
+            continue;
+        }
+        insert(&mut policy, &mut live, capacity, key);
+    }
+    // 最后删除一批旧键，再插入一批新键并访问其中一半，让刚进入的键也带着访问记录
+    for key in live.drain(..capacity / 4).collect::<Vec<_>>() {
+        policy.on_remove(&key);
+    }
+    for key in 1000..1000 + capacity as u64 / 4 {
+        insert(&mut policy, &mut live, capacity, key);
+        if key.is_multiple_of(2) {
+            for _ in 0..16 {
+                policy.on_access(&key);
+            }
+        }
+    }
+
+    let order = policy.eviction_order().unwrap();
+    let mut evicted = Vec::new();
+    while let Some(victim) = policy.choose_victim() {
+        evicted.push(victim);
+    }
+    assert_eq!(order.len(), live.len());
+    assert_eq!(order, evicted);
+}
+
+fn insert<P: EvictionPolicy<u64>>(policy: &mut P, live: &mut Vec<u64>, capacity: usize, key: u64) {
+    policy.before_insert(&key);
+    if live.len() == capacity {
+        let victim = policy.choose_victim().unwrap();
+        live.retain(|&live_key| live_key != victim);
+    }
+    policy.on_insert(&key);
+    live.push(key);
+}
+
+// 测试所有淘汰策略给出的遍历顺序就是之后没有访问时的淘汰顺序
+#[test]
+fn test_eviction_order_matches_victims() {
+    check_eviction_order(LruPolicy::new(), 20);
+    check_eviction_order(LfuPolicy::new(), 20);
+    check_eviction_order(ArcPolicy::new(20), 20);
+    check_eviction_order(ClockPolicy::new(20), 20);
+    check_eviction_order(SievePolicy::new(), 20);
+    check_eviction_order(S3FifoPolicy::new(20), 20);
+    check_eviction_order(S3FifoPolicy::new(100), 100);
+    check_eviction_order(TinyLfuPolicy::new(200), 200);
+}
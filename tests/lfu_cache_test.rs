@@ -0,0 +1,128 @@
+use std::time::Duration;
+use localcache::lib::lfucache::LfuCache;
+use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
+
+#[test]
+fn test_lfu_cache_insert_and_get() {
+    let mut cache: LfuCache<String, String> = LfuCache::new(100);
+
+    // 测试插入和获取
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 测试不存在的键
+    assert_eq!(cache.get(&"nonexistent".to_string()), None);
+}
+
+#[test]
+fn test_lfu_cache_evicts_least_frequent() {
+    let mut cache: LfuCache<String, i32> = LfuCache::new(2);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    cache.get(&"key1".to_string());
+    cache.get(&"key1".to_string());
+    assert_eq!(cache.frequency(&"key1".to_string()), Some(3));
+
+    // key2 访问次数最少，被淘汰
+    cache.insert("key3".to_string(), 3);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"key2".to_string()), None);
+    assert_eq!(cache.get(&"key1".to_string()), Some(1));
+    assert_eq!(cache.get(&"key3".to_string()), Some(3));
+}
+
+#[test]
+fn test_lfu_cache_ties_break_by_recency() {
+    let mut cache: LfuCache<String, i32> = LfuCache::new(3);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    cache.insert("key3".to_string(), 3);
+    cache.get(&"key2".to_string());
+    cache.get(&"key1".to_string());
+
+    // key1 和 key2 频次相同，key2 更久未使用；key3 频次最低
+    cache.insert("key4".to_string(), 4);
+    assert!(!cache.contains_key(&"key3".to_string()));
+    cache.insert("key5".to_string(), 5);
+    assert!(!cache.contains_key(&"key4".to_string()));
+
+    cache.get(&"key5".to_string());
+    // 此时三者频次均为 2，key2 最久未使用
+    cache.insert("key6".to_string(), 6);
+    assert!(!cache.contains_key(&"key2".to_string()));
+    assert!(cache.contains_key(&"key1".to_string()));
+    assert!(cache.contains_key(&"key5".to_string()));
+}
+
+#[test]
+fn test_lfu_cache_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache: LfuCache<String, String> = LfuCache::new(100).with_clock(clock.clone());
+
+    // 插入带TTL的值
+    cache.insert_with_ttl(
+        "key1".to_string(),
+        "value1".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+
+    // 应该能获取到值
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+
+    // 应该获取不到值（已过期）
+    assert_eq!(cache.get(&"key1".to_string()), None);
+}
+
+#[test]
+fn test_lfu_cache_remove_and_clear() {
+    let mut cache: LfuCache<String, i32> = LfuCache::new(2);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    cache.get(&"key2".to_string());
+    assert_eq!(cache.remove(&"key1".to_string()), Some(1));
+    assert_eq!(cache.remove(&"key1".to_string()), None);
+
+    // 删除后最低频次桶更新，仍能正常淘汰
+    cache.insert("key3".to_string(), 3);
+    cache.insert("key4".to_string(), 4);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains_key(&"key2".to_string()));
+    assert!(cache.contains_key(&"key4".to_string()));
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+// 测试最低频次桶被删空后，淘汰从下一个频次桶开始
+#[test]
+fn test_lfu_cache_min_bucket_emptied() {
+    let mut cache: LfuCache<i32, i32> = LfuCache::new(3);
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+    cache.insert(3, 3);
+    for _ in 0..3 {
+        cache.get(&1);
+    }
+    cache.get(&2);
+    // 删除唯一频次为 1 的键 3，最低频次变为 2
+    cache.remove(&3);
+    cache.insert(4, 4);
+    cache.get(&4);
+    cache.get(&4);
+    assert_eq!(cache.frequency(&4), Some(3));
+
+    // 键 2 频次最低（2）
+    cache.insert(5, 5);
+    assert!(!cache.contains_key(&2));
+    // 新插入的键 5 频次为 1，下一个被淘汰
+    cache.insert(6, 6);
+    assert!(!cache.contains_key(&5));
+    assert_eq!(cache.frequency(&1), Some(4));
+}
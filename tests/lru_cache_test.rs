@@ -1,6 +1,7 @@
 use std::time::Duration;
 use localcache::lib::lrucache::LruCache;
 use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
 #[test]
 fn test_lru_cache_insert_and_get() {
     let mut cache: LruCache<String, String> = LruCache::new(100);
@@ -21,14 +22,38 @@ fn test_lru_cache_with_size_limit() {
     cache.insert("key2".to_string(), "value2".to_string());
     cache.insert("key3".to_string(), "value3".to_string());
     
-    // LRU缓存应该有大小限制，但目前的实现还没有完全实现LRU淘汰机制
-    // 这里只是测试基本功能
-    assert_eq!(cache.len(), 3);
+    // 超出容量时淘汰最久未使用的 key1
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"key1".to_string()), None);
+    assert_eq!(cache.get(&"key2".to_string()), Some("value2".to_string()));
+    assert_eq!(cache.get(&"key3".to_string()), Some("value3".to_string()));
+}
+
+#[test]
+fn test_lru_cache_access_promotes() {
+    let mut cache: LruCache<String, String> = LruCache::new(2);
+    
+    cache.insert("key1".to_string(), "value1".to_string());
+    cache.insert("key2".to_string(), "value2".to_string());
+    
+    // 访问 key1 后，最久未使用的变为 key2
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+    cache.insert("key3".to_string(), "value3".to_string());
+    assert_eq!(cache.get(&"key2".to_string()), None);
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+    
+    // 覆盖写入同样算作访问
+    cache.insert("key3".to_string(), "value3b".to_string());
+    cache.insert("key4".to_string(), "value4".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), None);
+    assert_eq!(cache.get(&"key3".to_string()), Some("value3b".to_string()));
+    assert_eq!(cache.len(), 2);
 }
 
 #[test]
 fn test_lru_cache_with_ttl() {
-    let mut cache: LruCache<String, String> = LruCache::new(100);
+    let clock = MockClock::new();
+    let mut cache: LruCache<String, String> = LruCache::new(100).with_clock(clock.clone());
     
     // 插入带TTL的值
     cache.insert_with_ttl(
@@ -40,8 +65,8 @@ fn test_lru_cache_with_ttl() {
     // 应该能获取到值
     assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
     
-    // 等待过期
-    std::thread::sleep(Duration::from_millis(150));
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
     
     // 应该获取不到值（已过期）
     assert_eq!(cache.get(&"key1".to_string()), None);
@@ -71,4 +96,43 @@ fn test_lru_cache_clear() {
     cache.clear();
     assert_eq!(cache.len(), 0);
     assert!(cache.is_empty());
-}
\ No newline at end of file
+}
+#[test]
+fn test_lru_cache_peek_does_not_promote() {
+    let mut cache: LruCache<String, String> = LruCache::new(2);
+
+    cache.insert("key1".to_string(), "value1".to_string());
+    cache.insert("key2".to_string(), "value2".to_string());
+
+    // peek 和 contains_key 不改变淘汰顺序
+    assert_eq!(cache.peek(&"key1".to_string()), Some("value1".to_string()));
+    assert!(cache.contains_key(&"key1".to_string()));
+    cache.insert("key3".to_string(), "value3".to_string());
+
+    assert!(!cache.contains_key(&"key1".to_string()));
+    assert_eq!(cache.peek(&"key1".to_string()), None);
+    assert!(cache.contains_key(&"key2".to_string()));
+}
+
+#[test]
+fn test_lru_cache_evicts_expired_before_live_entries() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<String, String> = LruCache::new(2).with_clock(clock.clone());
+
+    cache.insert("key1".to_string(), "value1".to_string());
+    cache.insert_with_ttl(
+        "key2".to_string(),
+        "value2".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+    assert_eq!(cache.len(), 1);
+
+    // 缓存已满时先清理过期的 key2，最久未使用的 key1 得以保留
+    cache.insert("key3".to_string(), "value3".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+    assert_eq!(cache.get(&"key3".to_string()), Some("value3".to_string()));
+    assert_eq!(cache.purge_expired(), 0);
+}
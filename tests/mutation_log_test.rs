@@ -0,0 +1,265 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use localcache::lib::basiccache::BasicCache;
+use localcache::lib::cache::{Cache, CacheType, new_cache, new_cache_with_clock};
+use localcache::lib::clock::MockClock;
+use localcache::lib::loggedcache::{FsyncPolicy, LoggedCache, ReplayReport};
+use localcache::lib::lrucache::LruCache;
+
+fn temp_path(name: &str, ext: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("localcache-{}-{}.{}", name, std::process::id(), ext));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn open(path: &PathBuf, fsync: FsyncPolicy) -> LoggedCache<String, u64> {
+    LoggedCache::open(new_cache(CacheType::Basic), path, fsync).unwrap()
+}
+
+// 测试重新打开后按顺序重放插入、删除和清空
+#[test]
+fn test_log_replay() {
+    let path = temp_path("log-replay", "log");
+    {
+        let mut cache = open(&path, FsyncPolicy::Always);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        cache.clear();
+        cache.insert("c".to_string(), 3);
+        cache.insert_with_ttl("d".to_string(), 4, None);
+        cache.insert("c".to_string(), 30);
+        cache.remove(&"d".to_string());
+        cache.insert("e".to_string(), 5);
+    }
+
+    let mut cache = open(&path, FsyncPolicy::Never);
+    assert_eq!(cache.replay_report(), ReplayReport { records: 8, discarded_bytes: 0 });
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&"c".to_string()), Some(30));
+    assert_eq!(cache.get(&"e".to_string()), Some(5));
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试 TTL 按绝对时间记录，重放时已过期的写入被跳过
+#[test]
+fn test_log_replay_ttl() {
+    let path = temp_path("log-ttl", "log");
+    let clock = MockClock::new();
+    let open_mock = |clock: &MockClock| -> LoggedCache<String, u64> {
+        let cache = new_cache_with_clock(CacheType::Basic, clock.clone());
+        LoggedCache::open_with_clock(cache, &path, FsyncPolicy::Never, clock.clone()).unwrap()
+    };
+    {
+        let mut cache = open_mock(&clock);
+        cache.insert("old".to_string(), 1);
+        cache.insert_with_ttl("old".to_string(), 2, Some(Duration::from_secs(20)));
+        cache.insert_with_ttl("short".to_string(), 3, Some(Duration::from_secs(20)));
+        cache.insert_with_expiry("long".to_string(), 4, Some(Duration::from_secs(3600)), Some(Duration::from_secs(600)));
+    }
+    clock.advance(Duration::from_secs(50));
+
+    let mut cache = open_mock(&clock);
+    assert_eq!(cache.replay_report().records, 4);
+    assert_eq!(cache.get(&"old".to_string()), None);
+    assert_eq!(cache.get(&"short".to_string()), None);
+    assert_eq!(cache.get(&"long".to_string()), Some(4));
+    // 剩余的 TTL 按记录的绝对时间计算
+    clock.advance(Duration::from_secs(3500));
+    assert_eq!(cache.get(&"long".to_string()), None);
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试写了一半的尾部被检测出来、丢弃并截掉
+#[test]
+fn test_log_truncated_tail() {
+    let path = temp_path("log-truncated", "log");
+    {
+        let mut cache = open(&path, FsyncPolicy::Always);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+    }
+    let full = std::fs::read(&path).unwrap();
+    let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(full.len() as u64 - 3).unwrap();
+    drop(file);
+
+    {
+        let mut cache = open(&path, FsyncPolicy::Always);
+        let report = cache.replay_report();
+        assert_eq!(report.records, 1);
+        assert!(report.discarded_bytes > 0);
+        assert_eq!(cache.get(&"a".to_string()), Some(1));
+        assert_eq!(cache.get(&"b".to_string()), None);
+        // 新记录接在最后一条完整记录之后
+        cache.insert("c".to_string(), 3);
+    }
+
+    let mut cache = open(&path, FsyncPolicy::Never);
+    assert_eq!(cache.replay_report(), ReplayReport { records: 2, discarded_bytes: 0 });
+    assert_eq!(cache.get(&"c".to_string()), Some(3));
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试校验和不匹配的记录及其之后的内容都被丢弃
+#[test]
+fn test_log_corrupted_record() {
+    let path = temp_path("log-corrupted", "log");
+    let first_len;
+    {
+        let mut cache = open(&path, FsyncPolicy::Always);
+        cache.insert("a".to_string(), 1);
+        first_len = std::fs::metadata(&path).unwrap().len() as usize;
+        cache.insert("b".to_string(), 2);
+        cache.insert("c".to_string(), 3);
+    }
+    let mut bytes = std::fs::read(&path).unwrap();
+    let total = bytes.len();
+    bytes[first_len + 10] ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+
+    let mut cache = open(&path, FsyncPolicy::Never);
+    let report = cache.replay_report();
+    assert_eq!(report.records, 1);
+    assert_eq!(report.discarded_bytes, (total - first_len) as u64);
+    assert_eq!(cache.get(&"a".to_string()), Some(1));
+    assert_eq!(cache.get(&"b".to_string()), None);
+    assert_eq!(cache.get(&"c".to_string()), None);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), first_len as u64);
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试校验通过却无法解析的记录返回错误，不截断日志
+#[test]
+fn test_log_undecodable_record_kept() {
+    let path = temp_path("log-undecodable", "log");
+    {
+        let mut cache = open(&path, FsyncPolicy::Always);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+    }
+    let len = std::fs::metadata(&path).unwrap().len();
+
+    // 用不同的键值类型打开，记录完整但无法解析
+    let result = LoggedCache::<u64, u64>::open(Box::new(BasicCache::new()), &path, FsyncPolicy::Never);
+    assert_eq!(result.err().map(|error| error.kind()), Some(std::io::ErrorKind::InvalidData));
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), len);
+
+    let mut cache = open(&path, FsyncPolicy::Never);
+    assert_eq!(cache.replay_report(), ReplayReport { records: 2, discarded_bytes: 0 });
+    assert_eq!(cache.get(&"b".to_string()), Some(2));
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试后台重写把日志压缩为存活条目，重写期间的写入不会丢失
+#[test]
+fn test_log_rewrite() {
+    let path = temp_path("log-rewrite", "log");
+    {
+        let mut cache = open(&path, FsyncPolicy::EverySecond);
+        for round in 0..50 {
+            for i in 0..10 {
+                cache.insert(format!("key{}", i), round);
+            }
+        }
+        cache.remove(&"key9".to_string());
+        let before = std::fs::metadata(&path).unwrap().len();
+
+        cache.rewrite();
+        assert!(cache.is_rewriting());
+        cache.insert("key0".to_string(), 100);
+        cache.remove(&"key8".to_string());
+        cache.wait_for_rewrite().unwrap();
+        assert!(!cache.is_rewriting());
+        assert!(std::fs::metadata(&path).unwrap().len() < before / 10);
+        cache.insert("key1".to_string(), 101);
+    }
+
+    let mut cache = open(&path, FsyncPolicy::Never);
+    assert_eq!(cache.replay_report().records, 1 + 9 + 3);
+    assert_eq!(cache.len(), 8);
+    assert_eq!(cache.get(&"key0".to_string()), Some(100));
+    assert_eq!(cache.get(&"key1".to_string()), Some(101));
+    assert_eq!(cache.get(&"key2".to_string()), Some(49));
+    assert_eq!(cache.get(&"key8".to_string()), None);
+    assert_eq!(cache.get(&"key9".to_string()), None);
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试启动时先恢复快照再重放日志，检查点之后日志被清空
+#[test]
+fn test_log_recover_with_snapshot() {
+    let snapshot = temp_path("log-recover", "snap");
+    let path = temp_path("log-recover", "log");
+    {
+        let mut cache = LoggedCache::recover(Box::new(BasicCache::new()), &snapshot, &path, FsyncPolicy::Always).unwrap();
+        cache.insert(1, "one".to_string());
+        cache.insert(2, "two".to_string());
+        assert_eq!(cache.checkpoint(&snapshot).unwrap(), 2);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+        cache.remove(&1);
+        cache.insert(3, "three".to_string());
+    }
+
+    let mut cache: LoggedCache<i32, String> =
+        LoggedCache::recover(Box::new(LruCache::new(10)), &snapshot, &path, FsyncPolicy::Never).unwrap();
+    assert_eq!(cache.replay_report().records, 2);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), Some("two".to_string()));
+    assert_eq!(cache.get(&3), Some("three".to_string()));
+    std::fs::remove_file(&snapshot).unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试检查点之后删除的条目在重写并恢复后不会从快照里复活
+#[test]
+fn test_log_rewrite_after_checkpoint() {
+    let snapshot = temp_path("log-rewrite-checkpoint", "snap");
+    let path = temp_path("log-rewrite-checkpoint", "log");
+    {
+        let mut cache = LoggedCache::recover(Box::new(BasicCache::new()), &snapshot, &path, FsyncPolicy::Always).unwrap();
+        cache.insert(1, "one".to_string());
+        cache.insert(2, "two".to_string());
+        cache.checkpoint(&snapshot).unwrap();
+        cache.remove(&1);
+        cache.clear();
+        cache.insert(3, "three".to_string());
+        cache.rewrite();
+        cache.wait_for_rewrite().unwrap();
+    }
+
+    let mut cache: LoggedCache<i32, String> =
+        LoggedCache::recover(Box::new(BasicCache::new()), &snapshot, &path, FsyncPolicy::Never).unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&3), Some("three".to_string()));
+    std::fs::remove_file(&snapshot).unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试日志超过阈值且翻倍后自动重写，日志长度保持有界
+#[test]
+fn test_log_auto_rewrite() {
+    let path = temp_path("log-auto-rewrite", "log");
+    let mut rewrites = 0;
+    {
+        let mut cache = open(&path, FsyncPolicy::Never).with_auto_rewrite(1024);
+        for round in 0..500 {
+            for i in 0..5 {
+                cache.insert(format!("key{}", i), round);
+                if cache.is_rewriting() {
+                    rewrites += 1;
+                    cache.wait_for_rewrite().unwrap();
+                }
+                assert!(std::fs::metadata(&path).unwrap().len() < 1100);
+            }
+        }
+    }
+    assert!(rewrites > 10);
+
+    let mut cache = open(&path, FsyncPolicy::Never);
+    assert_eq!(cache.len(), 5);
+    assert_eq!(cache.get(&"key0".to_string()), Some(499));
+    assert_eq!(cache.get(&"key4".to_string()), Some(499));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+use localcache::lib::cache::{Cache, new_cache_with_policy};
+use localcache::lib::lrucache::LruPolicy;
+use localcache::lib::policy::EvictionPolicy;
+use localcache::lib::policycache::PolicyCache;
+
+/// 自定义的 FIFO 策略：只按插入顺序淘汰
+#[derive(Default)]
+struct FifoPolicy {
+    queue: VecDeque<String>,
+}
+
+impl EvictionPolicy<String> for FifoPolicy {
+    fn on_insert(&mut self, key: &String) {
+        self.queue.push_back(key.clone());
+    }
+
+    fn on_access(&mut self, _key: &String) {}
+
+    fn on_remove(&mut self, key: &String) {
+        self.queue.retain(|k| k != key);
+    }
+
+    fn choose_victim(&mut self) -> Option<String> {
+        self.queue.pop_front()
+    }
+
+    fn clear(&mut self) {
+        self.queue.clear();
+    }
+}
+
+#[test]
+fn test_policy_cache_with_custom_policy() {
+    let mut cache = PolicyCache::with_policy(FifoPolicy::default(), 2);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    // FIFO 不关心访问，key1 仍然最先被淘汰
+    assert_eq!(cache.get(&"key1".to_string()), Some(1));
+    cache.insert("key3".to_string(), 3);
+
+    assert_eq!(cache.get(&"key1".to_string()), None);
+    assert_eq!(cache.get(&"key2".to_string()), Some(2));
+    assert_eq!(cache.get(&"key3".to_string()), Some(3));
+    assert_eq!(cache.policy().queue.len(), 2);
+}
+
+#[test]
+fn test_policy_cache_notifies_policy_on_remove_and_clear() {
+    let mut cache = PolicyCache::with_policy(FifoPolicy::default(), 10);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    assert_eq!(cache.remove(&"key1".to_string()), Some(1));
+    assert_eq!(cache.policy().queue, vec!["key2".to_string()]);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert!(cache.policy().queue.is_empty());
+}
+
+#[test]
+fn test_policy_cache_unbounded() {
+    let mut cache = PolicyCache::unbounded(FifoPolicy::default());
+
+    for i in 0..100 {
+        cache.insert(i.to_string(), i);
+    }
+    assert_eq!(cache.len(), 100);
+}
+
+#[test]
+fn test_new_cache_with_policy() {
+    let mut custom = new_cache_with_policy::<String, i32, _>(FifoPolicy::default(), Some(1));
+    custom.insert("key1".to_string(), 1);
+    custom.insert("key2".to_string(), 2);
+    assert!(!custom.contains_key(&"key1".to_string()));
+    assert_eq!(custom.len(), 1);
+
+    let mut lru = new_cache_with_policy::<String, i32, _>(LruPolicy::new(), Some(2));
+    lru.insert("key1".to_string(), 1);
+    lru.insert("key2".to_string(), 2);
+    lru.get(&"key1".to_string());
+    lru.insert("key3".to_string(), 3);
+    assert!(lru.contains_key(&"key1".to_string()));
+    assert!(!lru.contains_key(&"key2".to_string()));
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+use localcache::lib::cache::{Cache, CacheExt, CacheType, new_cache};
+use localcache::lib::clock::MockClock;
+use localcache::lib::concurrentcache::ConcurrentCache;
+use localcache::lib::lrucache::LruCache;
+
+// 测试未命中时加载并写入，命中时不再调用加载函数
+#[test]
+fn test_get_or_insert_with() {
+    let mut cache: LruCache<String, i32> = LruCache::new(10);
+    let mut loads = 0;
+
+    let value = cache.get_or_insert_with("key".to_string(), || {
+        loads += 1;
+        42
+    });
+    assert_eq!(value, 42);
+    let value = cache.get_or_insert_with("key".to_string(), || {
+        loads += 1;
+        0
+    });
+    assert_eq!(value, 42);
+    assert_eq!(loads, 1);
+}
+
+// 测试通过工厂创建的 Box<dyn Cache> 也能使用
+#[test]
+fn test_get_or_insert_with_boxed_cache() {
+    let mut cache = new_cache::<String, i32>(CacheType::Sieve(10));
+    assert_eq!(cache.get_or_insert_with("key".to_string(), || 1), 1);
+    assert_eq!(cache.get(&"key".to_string()), Some(1));
+}
+
+// 测试加载失败时不写入任何内容
+#[test]
+fn test_try_get_or_insert_with_error() {
+    let mut cache: LruCache<String, i32> = LruCache::new(10);
+
+    let result: Result<i32, String> = cache.try_get_or_insert_with("key".to_string(), || Err("失败".to_string()));
+    assert_eq!(result, Err("失败".to_string()));
+    assert!(!cache.contains_key(&"key".to_string()));
+    assert!(cache.is_empty());
+
+    let result: Result<i32, String> = cache.try_get_or_insert_with("key".to_string(), || Ok(7));
+    assert_eq!(result, Ok(7));
+    assert_eq!(cache.get(&"key".to_string()), Some(7));
+}
+
+// 测试加载的值使用指定的 TTL，过期后重新加载
+#[test]
+fn test_get_or_insert_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<String, i32> = LruCache::new(10).with_clock(clock.clone());
+
+    let value = cache.get_or_insert_with_ttl("key".to_string(), Some(Duration::from_secs(5)), || 1);
+    assert_eq!(value, 1);
+    clock.advance(Duration::from_secs(6));
+
+    let value: Result<i32, ()> =
+        cache.try_get_or_insert_with_ttl("key".to_string(), Some(Duration::from_secs(5)), || Ok(2));
+    assert_eq!(value, Ok(2));
+    assert_eq!(cache.get(&"key".to_string()), Some(2));
+}
+
+// 测试并发缓存的读穿透
+#[test]
+fn test_concurrent_cache_get_or_insert_with() {
+    let clock = MockClock::new();
+    let cache = ConcurrentCache::with_clock(CacheType::Lru(10), 2, clock.clone());
+
+    assert_eq!(cache.get_or_insert_with(1, || 10), 10);
+    assert_eq!(cache.get_or_insert_with(1, || 20), 10);
+
+    let result: Result<i32, &str> = cache.try_get_or_insert_with(2, || Err("失败"));
+    assert_eq!(result, Err("失败"));
+    assert!(!cache.contains_key(&2));
+
+    assert_eq!(cache.get_or_insert_with_ttl(3, Some(Duration::from_secs(5)), || 30), 30);
+    clock.advance(Duration::from_secs(6));
+    assert_eq!(cache.get(&3), None);
+}
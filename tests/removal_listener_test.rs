@@ -0,0 +1,135 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use localcache::lib::cache::{Cache, CacheType, RemovalCause, RemovalListener, new_cache_with_clock};
+use localcache::lib::clock::MockClock;
+use localcache::lib::concurrentcache::ConcurrentCache;
+use localcache::lib::lrucache::LruCache;
+
+type Events = Arc<Mutex<Vec<(i32, i32, RemovalCause)>>>;
+
+fn recorder() -> (Events, RemovalListener<i32, i32>) {
+    let events: Events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let listener = Arc::new(move |key: &i32, value: &i32, cause: RemovalCause| {
+        sink.lock().unwrap().push((*key, *value, cause));
+    });
+    (events, listener)
+}
+
+fn take(events: &Events) -> Vec<(i32, i32, RemovalCause)> {
+    std::mem::take(&mut *events.lock().unwrap())
+}
+
+// 测试每种原因都会通知监听器
+#[test]
+fn test_removal_causes() {
+    let clock = MockClock::new();
+    let (events, listener) = recorder();
+    let mut cache: LruCache<i32, i32> = LruCache::new(2)
+        .with_clock(clock.clone())
+        .with_removal_listener(listener);
+
+    cache.insert(1, 10);
+    cache.insert(1, 11);
+    assert_eq!(take(&events), vec![(1, 10, RemovalCause::Replaced)]);
+
+    cache.insert(2, 20);
+    cache.insert(3, 30);
+    assert_eq!(take(&events), vec![(1, 11, RemovalCause::Evicted)]);
+
+    cache.remove(&2);
+    assert_eq!(take(&events), vec![(2, 20, RemovalCause::Explicit)]);
+    cache.remove(&2);
+    assert!(take(&events).is_empty());
+
+    cache.insert_with_ttl(4, 40, Some(Duration::from_secs(1)));
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(cache.purge_expired(), 1);
+    assert_eq!(take(&events), vec![(4, 40, RemovalCause::Expired)]);
+
+    cache.clear();
+    assert_eq!(take(&events), vec![(3, 30, RemovalCause::Cleared)]);
+}
+
+// 测试读取过期条目和清空时未清理的过期条目按过期通知
+#[test]
+fn test_removal_expired_on_get_and_clear() {
+    let clock = MockClock::new();
+    let (events, listener) = recorder();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_clock(clock.clone());
+    cache.set_removal_listener(listener);
+
+    cache.insert_with_ttl(1, 10, Some(Duration::from_secs(1)));
+    cache.insert_with_ttl(2, 20, Some(Duration::from_secs(3)));
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(take(&events), vec![(1, 10, RemovalCause::Expired)]);
+
+    clock.advance(Duration::from_secs(2));
+    cache.clear();
+    assert_eq!(take(&events), vec![(2, 20, RemovalCause::Expired)]);
+}
+
+// 测试工厂创建的每种缓存都支持监听器
+#[test]
+fn test_removal_listener_every_cache_type() {
+    for cache_type in [
+        CacheType::Basic,
+        CacheType::Lru(3),
+        CacheType::Lfu(3),
+        CacheType::Arc(3),
+        CacheType::TinyLfu(3),
+        CacheType::Clock(3),
+        CacheType::Sieve(3),
+        CacheType::S3Fifo(3),
+    ] {
+        let clock = MockClock::new();
+        let (events, listener) = recorder();
+        let mut cache = new_cache_with_clock::<i32, i32, _>(cache_type.clone(), clock.clone());
+        cache.set_removal_listener(listener);
+
+        cache.insert(1, 10);
+        cache.insert(1, 11);
+        cache.remove(&1);
+        cache.insert_with_ttl(2, 20, Some(Duration::from_secs(1)));
+        clock.advance(Duration::from_secs(2));
+        cache.purge_expired();
+        assert_eq!(
+            take(&events),
+            vec![
+                (1, 10, RemovalCause::Replaced),
+                (1, 11, RemovalCause::Explicit),
+                (2, 20, RemovalCause::Expired),
+            ],
+            "{:?}",
+            cache_type
+        );
+
+        // 有容量上限的缓存写满后会淘汰，淘汰和清空的条目合起来正好是写入的全部
+        for i in 0..10 {
+            cache.insert(i, i);
+        }
+        let evicted = take(&events);
+        assert!(evicted.iter().all(|(_, _, cause)| *cause == RemovalCause::Evicted));
+        cache.clear();
+        let cleared = take(&events);
+        assert!(cleared.iter().all(|(_, _, cause)| *cause == RemovalCause::Cleared));
+        assert_eq!(evicted.len() + cleared.len(), 10, "{:?}", cache_type);
+        if !matches!(cache_type, CacheType::Basic) {
+            assert_eq!(cleared.len(), 3, "{:?}", cache_type);
+        }
+    }
+}
+
+// 测试并发缓存的所有分片共享监听器
+#[test]
+fn test_removal_listener_concurrent_cache() {
+    let (events, listener) = recorder();
+    let cache = ConcurrentCache::new(CacheType::Basic, 4);
+    cache.set_removal_listener(listener);
+    for i in 0..20 {
+        cache.insert(i, i);
+    }
+    cache.clear();
+    assert_eq!(take(&events).len(), 20);
+}
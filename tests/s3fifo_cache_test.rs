@@ -0,0 +1,94 @@
+use std::time::Duration;
+use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
+use localcache::lib::s3fifocache::S3FifoCache;
+
+#[test]
+fn test_s3fifo_cache_insert_and_get() {
+    let mut cache: S3FifoCache<String, String> = S3FifoCache::new(100);
+
+    // 测试插入和获取
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 测试不存在的键
+    assert_eq!(cache.get(&"nonexistent".to_string()), None);
+}
+
+#[test]
+fn test_s3fifo_cache_filters_one_hit_wonders() {
+    let mut cache: S3FifoCache<i32, i32> = S3FifoCache::new(10);
+
+    for i in 0..10 {
+        cache.insert(i, i);
+    }
+    // 0..5 被访问过两次，淘汰时会被移入主队列
+    for i in 0..5 {
+        cache.get(&i);
+        cache.get(&i);
+    }
+
+    // 大量只访问一次的键只在小队列中流转
+    for i in 100..200 {
+        cache.insert(i, i);
+        assert!(cache.len() <= 10);
+    }
+    for i in 0..5 {
+        assert!(cache.contains_key(&i), "hot key {i} was evicted");
+    }
+    assert!(!cache.contains_key(&5));
+}
+
+#[test]
+fn test_s3fifo_cache_ghost_hit_goes_to_main() {
+    let mut cache: S3FifoCache<i32, i32> = S3FifoCache::new(10);
+
+    for i in 0..11 {
+        cache.insert(i, i);
+    }
+    // 0 从小队列淘汰，进入幽灵队列
+    assert!(!cache.contains_key(&0));
+
+    // 再次插入时直接进入主队列，不会被后续的新键挤出
+    cache.insert(0, 0);
+    for i in 100..150 {
+        cache.insert(i, i);
+    }
+    assert_eq!(cache.get(&0), Some(0));
+}
+
+#[test]
+fn test_s3fifo_cache_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache: S3FifoCache<String, String> = S3FifoCache::new(100).with_clock(clock.clone());
+
+    // 插入带TTL的值
+    cache.insert_with_ttl(
+        "key1".to_string(),
+        "value1".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+
+    // 应该能获取到值
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+
+    // 应该获取不到值（已过期）
+    assert_eq!(cache.get(&"key1".to_string()), None);
+}
+
+#[test]
+fn test_s3fifo_cache_remove_and_clear() {
+    let mut cache: S3FifoCache<String, i32> = S3FifoCache::new(10);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    assert_eq!(cache.remove(&"key1".to_string()), Some(1));
+    assert_eq!(cache.get(&"key1".to_string()), None);
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
@@ -0,0 +1,139 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+use localcache::lib::cache::CacheType;
+use localcache::lib::concurrentcache::ConcurrentCache;
+
+// 测试同一个键同时未命中时只加载一次，所有线程拿到同一个值
+#[test]
+fn test_single_flight_loads_once() {
+    let cache = Arc::new(ConcurrentCache::new(CacheType::Lru(100), 4));
+    let loads = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(16));
+
+    let handles: Vec<_> = (0..16)
+        .map(|_| {
+            let cache = Arc::clone(&cache);
+            let loads = Arc::clone(&loads);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                cache.get_or_insert_with("hot".to_string(), || {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(100));
+                    42
+                })
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 42);
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+    assert_eq!(cache.get(&"hot".to_string()), Some(42));
+}
+
+// 测试加载失败时所有等待者收到同一个错误，且不写入缓存
+#[test]
+fn test_single_flight_shares_error() {
+    let cache = Arc::new(ConcurrentCache::<String, i32>::new(CacheType::Lru(100), 4));
+    let loads = Arc::new(AtomicUsize::new(0));
+    let barrier = Arc::new(Barrier::new(8));
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let cache = Arc::clone(&cache);
+            let loads = Arc::clone(&loads);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                cache.try_get_or_insert_with("bad".to_string(), || {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(100));
+                    Err("后端不可用".to_string())
+                })
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), Err("后端不可用".to_string()));
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+    assert!(!cache.contains_key(&"bad".to_string()));
+}
+
+// 测试加载函数 panic 后等待者被唤醒，由其中一个重新加载
+#[test]
+fn test_single_flight_recovers_from_panic() {
+    let cache = Arc::new(ConcurrentCache::<String, i32>::new(CacheType::Lru(100), 4));
+    let loads = Arc::new(AtomicUsize::new(0));
+    let panicked = Arc::new(AtomicBool::new(false));
+
+    let spawn = |delay: u64| {
+        let cache = Arc::clone(&cache);
+        let loads = Arc::clone(&loads);
+        let panicked = Arc::clone(&panicked);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(delay));
+            cache.get_or_insert_with("key".to_string(), || {
+                thread::sleep(Duration::from_millis(100));
+                if !panicked.swap(true, Ordering::SeqCst) {
+                    panic!("加载失败");
+                }
+                loads.fetch_add(1, Ordering::SeqCst);
+                7
+            })
+        })
+    };
+
+    let leader = spawn(0);
+    let waiters: Vec<_> = (0..8).map(|_| spawn(30)).collect();
+
+    assert!(leader.join().is_err());
+    for waiter in waiters {
+        assert_eq!(waiter.join().unwrap(), 7);
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+    assert_eq!(cache.get(&"key".to_string()), Some(7));
+}
+
+// 测试不同的键各自加载，互不等待
+#[test]
+fn test_single_flight_distinct_keys() {
+    let cache = ConcurrentCache::new(CacheType::Basic, 4);
+    let loads = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for i in 0..8 {
+            let cache = &cache;
+            let loads = &loads;
+            scope.spawn(move || {
+                let value = cache.get_or_insert_with(i, || {
+                    loads.fetch_add(1, Ordering::SeqCst);
+                    i * 10
+                });
+                assert_eq!(value, i * 10);
+            });
+        }
+    });
+    assert_eq!(loads.load(Ordering::SeqCst), 8);
+    assert_eq!(cache.len(), 8);
+}
+
+// 测试一次未命中的加载只计一次未命中
+#[test]
+fn test_single_flight_counts_one_miss() {
+    let cache = ConcurrentCache::new(CacheType::Lru(10), 2);
+    cache.set_stats_enabled(true);
+    assert_eq!(cache.get_or_insert_with(1, || 10), 10);
+    let stats = cache.stats();
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.load_successes, 1);
+
+    assert_eq!(cache.get_or_insert_with(1, || unreachable!()), 10);
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses), (1, 1));
+}
@@ -0,0 +1,73 @@
+use localcache::lib::sketch::{CountMinSketch, Doorkeeper, TinyLfu};
+
+#[test]
+fn test_count_min_sketch_estimate() {
+    let mut sketch = CountMinSketch::new(64, 10_000);
+
+    for _ in 0..5 {
+        sketch.increment("hot");
+    }
+    sketch.increment("cold");
+
+    assert_eq!(sketch.estimate("hot"), 5);
+    assert_eq!(sketch.estimate("cold"), 1);
+    assert_eq!(sketch.estimate("missing"), 0);
+}
+
+#[test]
+fn test_count_min_sketch_saturates_at_four_bits() {
+    let mut sketch = CountMinSketch::new(64, 10_000);
+
+    for _ in 0..100 {
+        sketch.increment(&42u64);
+    }
+    assert_eq!(sketch.estimate(&42u64), 15);
+}
+
+#[test]
+fn test_count_min_sketch_ages_by_halving() {
+    let mut sketch = CountMinSketch::new(64, 20);
+
+    for _ in 0..8 {
+        assert!(!sketch.increment("a"));
+    }
+    assert_eq!(sketch.estimate("a"), 8);
+
+    // 达到老化周期后所有计数减半
+    let mut aged = false;
+    for i in 0..12 {
+        aged |= sketch.increment(&i);
+    }
+    assert!(aged);
+    assert_eq!(sketch.estimate("a"), 4);
+}
+
+#[test]
+fn test_doorkeeper_filters_first_occurrence() {
+    let mut doorkeeper = Doorkeeper::new(100);
+
+    assert!(!doorkeeper.contains("key"));
+    assert!(!doorkeeper.insert("key"));
+    assert!(doorkeeper.contains("key"));
+    assert!(doorkeeper.insert("key"));
+
+    doorkeeper.clear();
+    assert!(!doorkeeper.contains("key"));
+}
+
+#[test]
+fn test_tiny_lfu_admission() {
+    let mut admittor = TinyLfu::new(100);
+
+    for _ in 0..4 {
+        admittor.record("hot");
+    }
+    admittor.record("once");
+
+    // 第一次出现只记在门卫中
+    assert_eq!(admittor.frequency("once"), 1);
+    assert_eq!(admittor.frequency("hot"), 4);
+    assert!(admittor.admit("hot", "once"));
+    assert!(!admittor.admit("once", "hot"));
+    assert!(!admittor.admit("never", "once"));
+}
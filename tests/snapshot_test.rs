@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use localcache::lib::basiccache::BasicCache;
+use localcache::lib::cache::{Cache, CacheType, new_cache};
+use localcache::lib::clock::MockClock;
+use localcache::lib::codec::crc32;
+use localcache::lib::lrucache::LruCache;
+use localcache::lib::snapshot::{Snapshot, SnapshotError};
+
+fn snapshot_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("localcache-{}-{}.snap", name, std::process::id()))
+}
+
+/// 修改快照文件中的字节并重新计算校验和
+fn rewrite(path: &PathBuf, edit: impl FnOnce(&mut Vec<u8>)) {
+    let mut bytes = std::fs::read(path).unwrap();
+    bytes.truncate(bytes.len() - 4);
+    edit(&mut bytes);
+    let checksum = crc32(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    std::fs::write(path, bytes).unwrap();
+}
+
+// 测试保存后恢复到新缓存
+#[test]
+fn test_snapshot_roundtrip() {
+    let path = snapshot_path("roundtrip");
+    let mut cache: BasicCache<String, u64> = BasicCache::new();
+    for i in 0..100 {
+        cache.insert(format!("key{}", i), i);
+    }
+    assert_eq!(cache.save_snapshot(&path).unwrap(), 100);
+
+    let mut restored = new_cache::<String, u64>(CacheType::Basic);
+    assert_eq!(restored.load_snapshot(&path).unwrap(), 100);
+    for i in 0..100 {
+        assert_eq!(restored.get(&format!("key{}", i)), Some(i));
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试 LRU 恢复后保持原有的新旧顺序
+#[test]
+fn test_snapshot_keeps_lru_order() {
+    let path = snapshot_path("lru-order");
+    let mut cache: LruCache<i32, i32> = LruCache::new(3);
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+    cache.insert(3, 3);
+    cache.get(&1);
+    cache.save_snapshot(&path).unwrap();
+
+    let mut restored: LruCache<i32, i32> = LruCache::new(3);
+    restored.load_snapshot(&path).unwrap();
+    // 最久未使用的是 2，其次是 3
+    restored.insert(4, 4);
+    assert!(!restored.contains_key(&2));
+    restored.insert(5, 5);
+    assert!(!restored.contains_key(&3));
+    assert!(restored.contains_key(&1));
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试剩余 TTL 被保存，停机期间过期的条目不会恢复
+#[test]
+fn test_snapshot_ttl_and_downtime() {
+    let path = snapshot_path("ttl");
+    let clock = MockClock::new();
+    let mut cache: BasicCache<i32, i32> = BasicCache::new().with_clock(clock.clone());
+    cache.insert_with_ttl(1, 1, Some(Duration::from_secs(100)));
+    cache.insert_with_ttl(2, 2, Some(Duration::from_secs(7200)));
+    cache.insert_with_expiry(3, 3, None, Some(Duration::from_secs(600)));
+    cache.insert_with_expiry(4, 4, None, Some(Duration::from_secs(7200)));
+    cache.insert(5, 5);
+    cache.insert_with_ttl(6, 6, Some(Duration::from_secs(10)));
+    clock.advance(Duration::from_secs(20));
+    // 已过期的条目不会写入快照
+    assert_eq!(cache.save_snapshot(&path).unwrap(), 5);
+
+    // 把保存时刻改到一小时前，模拟停机一小时
+    rewrite(&path, |bytes| {
+        let secs = u64::from_le_bytes(bytes[10..18].try_into().unwrap()) - 3600;
+        bytes[10..18].copy_from_slice(&secs.to_le_bytes());
+    });
+
+    let clock = MockClock::new();
+    let mut restored: BasicCache<i32, i32> = BasicCache::new().with_clock(clock.clone());
+    assert_eq!(restored.load_snapshot(&path).unwrap(), 3);
+    assert_eq!(restored.peek(&1), None);
+    assert_eq!(restored.peek(&3), None);
+    assert_eq!(restored.peek(&5), Some(5));
+
+    // 键 2 剩余约 7200 - 20 - 3600 秒
+    clock.advance(Duration::from_secs(3570));
+    assert_eq!(restored.peek(&2), Some(2));
+    clock.advance(Duration::from_secs(20));
+    assert_eq!(restored.peek(&2), None);
+    // 键 4 的空闲计时重新开始
+    assert_eq!(restored.peek(&4), Some(4));
+    std::fs::remove_file(&path).unwrap();
+}
+
+// 测试损坏的文件被拒绝
+#[test]
+fn test_snapshot_rejects_corrupt_files() {
+    let path = snapshot_path("corrupt");
+    let mut cache: BasicCache<i32, String> = BasicCache::new();
+    cache.insert(1, "value".to_string());
+    cache.save_snapshot(&path).unwrap();
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 6;
+    bytes[last] ^= 0xff;
+    std::fs::write(&path, &bytes).unwrap();
+    let mut restored: BasicCache<i32, String> = BasicCache::new();
+    assert!(matches!(restored.load_snapshot(&path), Err(SnapshotError::ChecksumMismatch)));
+    assert!(restored.is_empty());
+
+    std::fs::write(&path, b"not a snapshot at all").unwrap();
+    assert!(matches!(restored.load_snapshot(&path), Err(SnapshotError::BadMagic)));
+
+    cache.save_snapshot(&path).unwrap();
+    rewrite(&path, |bytes| bytes[8..10].copy_from_slice(&99u16.to_le_bytes()));
+    assert!(matches!(restored.load_snapshot(&path), Err(SnapshotError::UnsupportedVersion(99))));
+
+    assert!(matches!(restored.load_snapshot(snapshot_path("missing")), Err(SnapshotError::Io(_))));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+use localcache::lib::cache::{Cache, CacheExt, CacheType, RemovalCause, new_cache_with_clock};
+use localcache::lib::clock::MockClock;
+use localcache::lib::concurrentcache::ConcurrentCache;
+use localcache::lib::lrucache::LruCache;
+use localcache::lib::stats::CacheStats;
+
+// 测试统计默认关闭
+#[test]
+fn test_stats_disabled_by_default() {
+    let mut cache: LruCache<i32, i32> = LruCache::new(10);
+    cache.insert(1, 1);
+    cache.get(&1);
+    cache.get(&2);
+    assert_eq!(cache.stats(), CacheStats::default());
+}
+
+// 测试命中、未命中和写入计数
+#[test]
+fn test_stats_hits_and_misses() {
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_stats();
+    cache.insert(1, 1);
+    cache.insert(1, 2);
+    cache.get(&1);
+    cache.get(&1);
+    cache.get(&1);
+    cache.get(&2);
+    // peek 和 contains_key 不计入
+    cache.peek(&3);
+    cache.contains_key(&3);
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 3);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.inserts, 2);
+    assert_eq!(stats.request_count(), 4);
+    assert_eq!(stats.hit_ratio(), 0.75);
+    assert_eq!(stats.miss_ratio(), 0.25);
+    assert_eq!(stats.removal_count(RemovalCause::Replaced), 1);
+}
+
+// 测试按原因统计删除
+#[test]
+fn test_stats_removals_by_cause() {
+    let clock = MockClock::new();
+    let mut cache = new_cache_with_clock::<i32, i32, _>(CacheType::Lru(2), clock.clone());
+    cache.set_stats_enabled(true);
+
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+    cache.insert(3, 3);
+    cache.remove(&2);
+    cache.insert_with_ttl(4, 4, Some(Duration::from_secs(1)));
+    clock.advance(Duration::from_secs(2));
+    cache.purge_expired();
+    cache.insert(5, 5);
+    cache.clear();
+
+    let stats = cache.stats();
+    assert_eq!(stats.evictions, 1);
+    assert_eq!(stats.explicit_removals, 1);
+    assert_eq!(stats.expirations, 1);
+    assert_eq!(stats.clears, 2);
+    assert_eq!(stats.removal_count(RemovalCause::Evicted), 1);
+}
+
+// 测试加载的成功、失败次数和耗时
+#[test]
+fn test_stats_loads() {
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_stats();
+    cache.get_or_insert_with(1, || {
+        std::thread::sleep(Duration::from_millis(5));
+        1
+    });
+    let _ = cache.try_get_or_insert_with(2, || Err::<i32, ()>(()));
+    cache.get_or_insert_with(1, || unreachable!());
+
+    let stats = cache.stats();
+    assert_eq!(stats.load_successes, 1);
+    assert_eq!(stats.load_failures, 1);
+    assert_eq!(stats.load_count(), 2);
+    assert!(stats.total_load_time >= Duration::from_millis(5));
+    assert!(stats.average_load_time() > Duration::ZERO);
+}
+
+// 测试清零和关闭
+#[test]
+fn test_stats_reset_and_disable() {
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_stats();
+    cache.get(&1);
+    cache.reset_stats();
+    assert_eq!(cache.stats(), CacheStats::default());
+    assert_eq!(cache.stats().hit_ratio(), 0.0);
+
+    cache.get(&1);
+    cache.set_stats_enabled(false);
+    cache.get(&1);
+    assert_eq!(cache.stats(), CacheStats::default());
+}
+
+// 测试并发缓存汇总各分片的统计
+#[test]
+fn test_stats_concurrent_cache() {
+    let cache = ConcurrentCache::new(CacheType::Basic, 4);
+    cache.set_stats_enabled(true);
+    for i in 0..10 {
+        cache.insert(i, i);
+    }
+    for i in 0..20 {
+        cache.get(&i);
+    }
+    cache.get_or_insert_with(100, || 100);
+
+    let stats = cache.stats();
+    assert_eq!(stats.inserts, 11);
+    assert_eq!(stats.hits, 10);
+    assert_eq!(stats.load_successes, 1);
+    cache.reset_stats();
+    assert_eq!(cache.stats(), CacheStats::default());
+}
+
+// 测试平均加载耗时：没有加载时为 0，加载次数超出 u32 时不截断
+#[test]
+fn test_stats_average_load_time() {
+    assert_eq!(CacheStats::default().average_load_time(), Duration::ZERO);
+    let stats = CacheStats {
+        load_successes: 1 << 32,
+        load_failures: 1 << 32,
+        total_load_time: Duration::from_secs(1 << 34),
+        ..CacheStats::default()
+    };
+    assert_eq!(stats.average_load_time(), Duration::from_secs(2));
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use localcache::lib::basiccache::BasicCache;
+use localcache::lib::cache::{Cache, RemovalCause};
+use localcache::lib::clock::MockClock;
+use localcache::lib::lrucache::LruCache;
+
+// 测试按标签删除所有相关条目
+#[test]
+fn test_invalidate_tag() {
+    let mut cache: BasicCache<&str, i32> = BasicCache::new();
+    cache.insert_with_tags("/users/1", 1, ["user:1"]);
+    cache.insert_with_tags("/users/1/orders", 2, ["user:1", "orders"]);
+    cache.insert_with_tags("/users/2/orders", 3, ["user:2", "orders"]);
+    cache.insert("/health", 4);
+
+    assert_eq!(cache.tagged_len("orders"), 2);
+    assert_eq!(cache.invalidate_tag("user:1"), 2);
+    assert!(!cache.contains_key(&"/users/1"));
+    assert!(!cache.contains_key(&"/users/1/orders"));
+    assert!(cache.contains_key(&"/users/2/orders"));
+    assert!(cache.contains_key(&"/health"));
+    // 被删除条目的其他标签也一并清理
+    assert_eq!(cache.tagged_len("orders"), 1);
+    assert_eq!(cache.invalidate_tag("user:1"), 0);
+    assert_eq!(cache.invalidate_tag("missing"), 0);
+}
+
+// 测试覆盖条目时旧标签被取代
+#[test]
+fn test_overwrite_replaces_tags() {
+    let mut cache: BasicCache<i32, i32> = BasicCache::new();
+    cache.insert_with_tags(1, 1, ["a", "b", "a"]);
+    assert_eq!(cache.tagged_len("a"), 1);
+    cache.insert_with_tags(1, 2, ["c"]);
+    assert_eq!(cache.tagged_len("a"), 0);
+    assert_eq!(cache.tagged_len("b"), 0);
+    assert_eq!(cache.invalidate_tag("a"), 0);
+    assert_eq!(cache.peek(&1), Some(2));
+
+    cache.insert(1, 3);
+    assert_eq!(cache.tagged_len("c"), 0);
+    assert_eq!(cache.peek(&1), Some(3));
+}
+
+// 测试淘汰、过期、删除和清空时清理标签
+#[test]
+fn test_tags_cleaned_on_removal() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(2).with_clock(clock.clone());
+    cache.insert_with_tags(1, 1, ["t"]);
+    cache.insert_with_tags(2, 2, ["t"]);
+    cache.insert_with_tags(3, 3, ["t"]);
+    assert_eq!(cache.tagged_len("t"), 2);
+
+    cache.remove(&2);
+    assert_eq!(cache.tagged_len("t"), 1);
+
+    cache.insert_with_tags_and_ttl(4, 4, Some(Duration::from_secs(1)), ["t", "short"]);
+    clock.advance(Duration::from_secs(2));
+    cache.purge_expired();
+    assert_eq!(cache.tagged_len("short"), 0);
+    assert_eq!(cache.tagged_len("t"), 1);
+
+    cache.clear();
+    assert_eq!(cache.tagged_len("t"), 0);
+}
+
+// 测试按标签删除通知监听器，已过期的条目按过期通知且不计数
+#[test]
+fn test_invalidate_tag_notifies() {
+    let clock = MockClock::new();
+    let removed = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&removed);
+    let mut cache: BasicCache<i32, i32> = BasicCache::new()
+        .with_clock(clock.clone())
+        .with_removal_listener(Arc::new(move |key: &i32, _: &i32, cause| sink.lock().unwrap().push((*key, cause))));
+    cache.insert_with_tags(1, 1, ["t"]);
+    cache.insert_with_tags_and_ttl(2, 2, Some(Duration::from_secs(1)), ["t"]);
+    clock.advance(Duration::from_secs(2));
+
+    assert_eq!(cache.invalidate_tag("t"), 1);
+    let mut removed = removed.lock().unwrap().clone();
+    removed.sort_by_key(|(key, _)| *key);
+    assert_eq!(removed, vec![(1, RemovalCause::Explicit), (2, RemovalCause::Expired)]);
+    assert!(cache.is_empty());
+}
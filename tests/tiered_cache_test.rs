@@ -0,0 +1,203 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use localcache::lib::cache::{Cache, CacheError, RemovalCause};
+use localcache::lib::clock::MockClock;
+use localcache::lib::lrucache::LruCache;
+use localcache::lib::tieredcache::TieredCache;
+
+fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("localcache-{}-{}", name, std::process::id()))
+}
+
+fn tiered(name: &str, memory: usize, disk_bytes: u64) -> TieredCache<u64, String> {
+    TieredCache::new(Box::new(LruCache::new(memory)), temp_dir(name), disk_bytes).unwrap()
+}
+
+// 测试内存层淘汰的条目降级到磁盘层，命中后提升回内存层
+#[test]
+fn test_tiered_demote_and_promote() {
+    let mut cache = tiered("tiered-promote", 2, 1 << 20);
+    cache.insert(1, "one".to_string());
+    cache.insert(2, "two".to_string());
+    cache.insert(3, "three".to_string());
+    assert_eq!(cache.memory_len(), 2);
+    assert_eq!(cache.disk_len(), 1);
+    assert_eq!(cache.len(), 3);
+    assert!(cache.contains_key(&1));
+    assert_eq!(cache.peek(&1), Some("one".to_string()));
+    assert_eq!(cache.disk_len(), 1);
+
+    // 提升键 1，内存层最久未使用的键 2 被降级
+    assert_eq!(cache.get(&1), Some("one".to_string()));
+    assert_eq!(cache.memory_len(), 2);
+    assert_eq!(cache.disk_len(), 1);
+    assert_eq!(cache.get(&2), Some("two".to_string()));
+    assert_eq!(cache.get(&3), Some("three".to_string()));
+    assert_eq!(cache.len(), 3);
+}
+
+// 测试覆盖和删除磁盘层中的键
+#[test]
+fn test_tiered_overwrite_and_remove() {
+    let mut cache = tiered("tiered-remove", 1, 1 << 20);
+    cache.insert(1, "one".to_string());
+    cache.insert(2, "two".to_string());
+    cache.insert(3, "three".to_string());
+    assert_eq!(cache.disk_len(), 2);
+
+    // 覆盖磁盘层中的键后旧值不会再出现
+    cache.insert(1, "uno".to_string());
+    assert_eq!(cache.disk_len(), 2);
+    assert_eq!(cache.get(&1), Some("uno".to_string()));
+    assert_eq!(cache.remove(&2), Some("two".to_string()));
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.len(), 2);
+
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.segment_count(), 0);
+}
+
+// 测试超出字节容量时按先进先出回收最旧的段
+#[test]
+fn test_tiered_segment_reclamation() {
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let mut cache = tiered("tiered-reclaim", 1, 1000).with_segment_size(250);
+    let sink = Arc::clone(&evicted);
+    cache.set_removal_listener(Arc::new(move |key: &u64, _: &String, cause| {
+        sink.lock().unwrap().push((*key, cause));
+    }));
+
+    let value = "x".repeat(50);
+    for i in 0..100 {
+        cache.insert(i, value.clone());
+    }
+    assert!(cache.disk_bytes() <= 1000);
+    assert!(cache.segment_count() <= 4);
+
+    // 最早降级的键被回收，最近的仍在磁盘层
+    assert!(!cache.contains_key(&0));
+    assert!(cache.contains_key(&98));
+    assert_eq!(cache.len(), 1 + cache.disk_len());
+    let evicted = evicted.lock().unwrap();
+    assert_eq!(evicted.len(), 100 - cache.len());
+    assert_eq!(evicted[0], (0, RemovalCause::Evicted));
+    assert!(evicted.iter().all(|(_, cause)| *cause == RemovalCause::Evicted));
+}
+
+// 测试同一段中多次降级、提升或删除的键在回收时只按最新位置淘汰一次
+#[test]
+fn test_tiered_reclaim_rewritten_keys() {
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let mut cache = tiered("tiered-reclaim-rewritten", 1, 400).with_segment_size(200);
+    let sink = Arc::clone(&evicted);
+    cache.set_removal_listener(Arc::new(move |key: &u64, value: &String, cause| {
+        sink.lock().unwrap().push((*key, value.clone(), cause));
+    }));
+
+    cache.insert(1, "one".to_string());
+    cache.insert(2, "two".to_string());
+    assert_eq!(cache.get(&1), Some("one".to_string()));
+    cache.insert(1, "uno".to_string());
+    cache.insert(3, "three".to_string());
+    assert_eq!(cache.remove(&2), Some("two".to_string()));
+    cache.insert(4, "four".to_string());
+    assert_eq!(cache.segment_count(), 1);
+
+    // 写满新段后回收第一段
+    let value = "x".repeat(50);
+    for i in 10..20 {
+        cache.insert(i, value.clone());
+    }
+    let evicted = evicted.lock().unwrap();
+    let reclaimed: Vec<_> = evicted.iter().filter(|(key, _, _)| *key < 10).collect();
+    assert_eq!(
+        reclaimed,
+        [
+            &(1, "one".to_string(), RemovalCause::Replaced),
+            &(2, "two".to_string(), RemovalCause::Explicit),
+            &(1, "uno".to_string(), RemovalCause::Evicted),
+            &(3, "three".to_string(), RemovalCause::Evicted),
+            &(4, "four".to_string(), RemovalCause::Evicted),
+        ]
+    );
+    assert!(!cache.contains_key(&1));
+    assert!(!cache.contains_key(&3));
+}
+
+// 测试显式 TTL 随条目降级，在磁盘层同样会过期
+#[test]
+fn test_tiered_ttl_on_disk() {
+    let clock = MockClock::new();
+    let memory = LruCache::new(1).with_clock(clock.clone());
+    let mut cache = TieredCache::new(Box::new(memory), temp_dir("tiered-ttl"), 1 << 20)
+        .unwrap()
+        .with_clock(clock.clone());
+    cache.insert_with_ttl(1u64, "one".to_string(), Some(Duration::from_secs(10)));
+    cache.insert(2, "two".to_string());
+    cache.insert(3, "three".to_string());
+    assert_eq!(cache.disk_len(), 2);
+
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(cache.peek(&1), Some("one".to_string()));
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(cache.peek(&1), None);
+    // 已过期但还没清理的条目不计入数量
+    assert_eq!(cache.disk_len(), 1);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.purge_expired(), 1);
+    assert_eq!(cache.disk_len(), 1);
+    assert_eq!(cache.get(&2), Some("two".to_string()));
+}
+
+// 测试段文件在缓存关闭后被删除
+#[test]
+fn test_tiered_cleans_up_segments() {
+    let dir = temp_dir("tiered-cleanup");
+    {
+        let mut cache = tiered("tiered-cleanup", 1, 1 << 20);
+        cache.insert(1, "one".to_string());
+        cache.insert(2, "two".to_string());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    }
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    std::fs::remove_dir(&dir).unwrap();
+}
+
+// 测试内存层拒绝写入时磁盘层的旧值被删除，拒绝提升的条目按淘汰通知
+#[test]
+fn test_tiered_memory_rejects_write() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    // 权重随测试调整，模拟提升时放不进内存层
+    let scale = Arc::new(AtomicU64::new(1));
+    let weigh_scale = Arc::clone(&scale);
+    let memory = LruCache::new(1).with_max_weight(10, move |_: &u64, value: &String| {
+        value.len() as u64 * weigh_scale.load(Ordering::SeqCst)
+    });
+    let mut cache = TieredCache::new(Box::new(memory), temp_dir("tiered-reject"), 1 << 20).unwrap();
+    let sink = Arc::clone(&events);
+    cache.set_removal_listener(Arc::new(move |key: &u64, value: &String, cause| {
+        sink.lock().unwrap().push((*key, value.clone(), cause));
+    }));
+
+    cache.insert(1, "one".to_string());
+    cache.insert(2, "two".to_string());
+    cache.insert(3, "three".to_string());
+    assert_eq!(cache.disk_len(), 2);
+    assert_eq!(
+        cache.try_insert(1, "x".repeat(50)),
+        Err(CacheError::EntryTooHeavy { weight: 50, max_weight: 10 })
+    );
+    assert_eq!(cache.peek(&1), None);
+    assert_eq!(cache.get(&1), None);
+
+    scale.store(100, Ordering::SeqCst);
+    assert_eq!(cache.get(&2), Some("two".to_string()));
+    assert!(!cache.contains_key(&2));
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![(1, "one".to_string(), RemovalCause::Replaced), (2, "two".to_string(), RemovalCause::Evicted)]
+    );
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+use localcache::lib::basiccache::BasicCache;
+use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
+use localcache::lib::lrucache::LruCache;
+
+// 测试 get 读取会延长空闲期限
+#[test]
+fn test_tti_extended_by_get() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_clock(clock.clone());
+    cache.insert_with_expiry(1, 1, None, Some(Duration::from_secs(10)));
+
+    for _ in 0..5 {
+        clock.advance(Duration::from_secs(8));
+        assert_eq!(cache.get(&1), Some(1));
+    }
+
+    clock.advance(Duration::from_secs(11));
+    assert_eq!(cache.get(&1), None);
+}
+
+// 测试 peek 和 contains_key 不会延长空闲期限
+#[test]
+fn test_tti_not_extended_by_peek() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_clock(clock.clone());
+    cache.insert_with_expiry(1, 1, None, Some(Duration::from_secs(10)));
+
+    clock.advance(Duration::from_secs(8));
+    assert_eq!(cache.peek(&1), Some(1));
+    assert!(cache.contains_key(&1));
+
+    clock.advance(Duration::from_secs(3));
+    assert_eq!(cache.peek(&1), None);
+    assert_eq!(cache.get(&1), None);
+}
+
+// 测试 TTL 和 TTI 同时设置时以先到期的为准
+#[test]
+fn test_ttl_and_tti_together() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10).with_clock(clock.clone());
+    cache.insert_with_expiry(1, 1, Some(Duration::from_secs(20)), Some(Duration::from_secs(10)));
+    cache.insert_with_expiry(2, 2, Some(Duration::from_secs(20)), Some(Duration::from_secs(10)));
+
+    // 键 1 持续被读取，但仍在 TTL 到期时过期；键 2 因空闲先过期
+    for _ in 0..2 {
+        clock.advance(Duration::from_secs(8));
+        assert_eq!(cache.get(&1), Some(1));
+    }
+    assert_eq!(cache.peek(&2), None);
+
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(cache.get(&1), None);
+}
+
+// 测试默认空闲时间和写入刷新空闲期限
+#[test]
+fn test_default_tti_and_overwrite() {
+    let clock = MockClock::new();
+    let mut cache: BasicCache<i32, i32> = BasicCache::new()
+        .with_clock(clock.clone())
+        .with_time_to_idle(Duration::from_secs(10));
+    cache.insert(1, 1);
+    cache.insert_with_ttl(2, 2, Some(Duration::from_secs(60)));
+
+    clock.advance(Duration::from_secs(8));
+    cache.insert(1, 10);
+
+    clock.advance(Duration::from_secs(8));
+    assert_eq!(cache.peek(&1), Some(10));
+    assert_eq!(cache.peek(&2), None);
+}
+
+// 测试主动清理只删除真正空闲过期的条目
+#[test]
+fn test_tti_purge_expired() {
+    let clock = MockClock::new();
+    let mut cache: LruCache<i32, i32> = LruCache::new(10)
+        .with_clock(clock.clone())
+        .with_time_to_idle(Duration::from_secs(10));
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+
+    clock.advance(Duration::from_secs(8));
+    assert_eq!(cache.get(&1), Some(1));
+
+    // 两个键最初的期限都已过去，但键 1 被读取过
+    clock.advance(Duration::from_secs(4));
+    assert_eq!(cache.purge_expired(), 1);
+    assert_eq!(cache.len(), 1);
+
+    clock.advance(Duration::from_secs(7));
+    assert_eq!(cache.purge_expired(), 1);
+    assert!(cache.is_empty());
+}
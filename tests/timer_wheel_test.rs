@@ -0,0 +1,151 @@
+use localcache::lib::timerwheel::TimerWheel;
+use std::time::{Duration, Instant};
+
+fn at(base: Instant, secs: u64) -> Instant {
+    base + Duration::from_secs(secs)
+}
+
+// 测试秒级到期
+#[test]
+fn test_timer_wheel_expires_seconds() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    wheel.schedule("a", at(base, 3));
+    wheel.schedule("b", at(base, 5));
+    assert_eq!(wheel.len(), 2);
+
+    assert!(wheel.advance(at(base, 2)).is_empty());
+    assert_eq!(wheel.advance(at(base, 3)), vec!["a"]);
+    assert!(wheel.advance(at(base, 4)).is_empty());
+    assert_eq!(wheel.advance(at(base, 10)), vec!["b"]);
+    assert!(wheel.is_empty());
+}
+
+// 测试同一秒内只取出已经到期的键
+#[test]
+fn test_timer_wheel_partial_second() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    wheel.schedule("early", base + Duration::from_millis(1100));
+    wheel.schedule("late", base + Duration::from_millis(1900));
+
+    assert_eq!(wheel.advance(base + Duration::from_millis(1500)), vec!["early"]);
+    assert_eq!(wheel.advance(base + Duration::from_millis(1950)), vec!["late"]);
+}
+
+// 测试分钟、小时和超过一天的键经过级联后按时到期
+#[test]
+fn test_timer_wheel_cascades_higher_levels() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    wheel.schedule("minute", at(base, 90));
+    wheel.schedule("hour", at(base, 2 * 3600 + 5));
+    wheel.schedule("days", at(base, 3 * 86400 + 7));
+
+    assert!(wheel.advance(at(base, 89)).is_empty());
+    assert_eq!(wheel.advance(at(base, 90)), vec!["minute"]);
+    assert!(wheel.advance(at(base, 2 * 3600 + 4)).is_empty());
+    assert_eq!(wheel.advance(at(base, 2 * 3600 + 5)), vec!["hour"]);
+    assert!(wheel.advance(at(base, 3 * 86400 + 6)).is_empty());
+    assert_eq!(wheel.advance(at(base, 3 * 86400 + 7)), vec!["days"]);
+    assert!(wheel.is_empty());
+}
+
+// 测试一次跨越很长时间也能取出全部到期的键
+#[test]
+fn test_timer_wheel_advance_far() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    for i in 0..100 {
+        wheel.schedule(i, at(base, i * 997));
+    }
+    let mut expired = wheel.advance(at(base, 100 * 997));
+    expired.sort();
+    assert_eq!(expired, (0..100).collect::<Vec<_>>());
+}
+
+// 测试取消和重新调度
+#[test]
+fn test_timer_wheel_cancel_and_reschedule() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    wheel.schedule("a", at(base, 5));
+    wheel.schedule("b", at(base, 5));
+    wheel.cancel(&"a");
+    wheel.schedule("b", at(base, 120));
+    assert_eq!(wheel.len(), 1);
+
+    assert!(wheel.advance(at(base, 60)).is_empty());
+    assert_eq!(wheel.advance(at(base, 120)), vec!["b"]);
+}
+
+// 测试最早到期时间
+#[test]
+fn test_timer_wheel_next_deadline() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    assert_eq!(wheel.next_deadline(), None);
+
+    wheel.schedule("hour", at(base, 3700));
+    wheel.schedule("minute", at(base, 75));
+    assert_eq!(wheel.next_deadline(), Some(at(base, 75)));
+
+    wheel.advance(at(base, 80));
+    assert_eq!(wheel.next_deadline(), Some(at(base, 3700)));
+    wheel.clear();
+    assert_eq!(wheel.next_deadline(), None);
+}
+
+// 测试绕回当前分钟槽的键不会被误当成最早到期
+#[test]
+fn test_timer_wheel_next_deadline_wrapped_slot() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    wheel.advance(at(base, 30));
+    // 3629 秒落在第 1 层的第 0 槽，与当前分钟同槽但晚一整轮
+    wheel.schedule("late", at(base, 30 + 3599));
+    wheel.schedule("early", at(base, 130));
+    assert_eq!(wheel.next_deadline(), Some(at(base, 130)));
+
+    assert_eq!(wheel.advance(at(base, 130)), vec!["early"]);
+    assert_eq!(wheel.next_deadline(), Some(at(base, 3629)));
+    assert_eq!(wheel.advance(at(base, 3629)), vec!["late"]);
+}
+
+// 测试长时间空闲后一次推进很快完成，且与逐个检查的结果一致
+#[test]
+fn test_timer_wheel_matches_brute_force() {
+    let base = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    let mut pending: Vec<(u64, Instant)> = Vec::new();
+    let mut seed = 12345u64;
+    let mut next = |bound: u64| {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) % bound
+    };
+    let mut now = 0;
+    for key in 0..2000 {
+        let deadline = at(base, now + next(3 * 86400)) + Duration::from_millis(next(1000));
+        wheel.schedule(key, deadline);
+        pending.push((key, deadline));
+        if key % 50 == 0 {
+            now += next(30 * 86400);
+            let time = at(base, now);
+            let mut expired = wheel.advance(time);
+            expired.sort();
+            let mut expected: Vec<u64> = pending.iter().filter(|(_, d)| *d <= time).map(|(k, _)| *k).collect();
+            expected.sort();
+            assert_eq!(expired, expected);
+            pending.retain(|(_, d)| *d > time);
+        }
+        assert_eq!(wheel.next_deadline(), pending.iter().map(|(_, d)| *d).min());
+    }
+
+    // 空闲 30 天后推进只处理有键的秒
+    let start = Instant::now();
+    let mut wheel = TimerWheel::starting_at(base);
+    wheel.schedule(0u64, at(base, 31 * 86400));
+    assert!(wheel.advance(at(base, 30 * 86400)).is_empty());
+    assert_eq!(wheel.advance(at(base, 31 * 86400)), vec![0]);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+use localcache::lib::cache::Cache;
+use localcache::lib::clock::MockClock;
+use localcache::lib::lrucache::LruCache;
+use localcache::lib::tinylfucache::TinyLfuCache;
+
+#[test]
+fn test_tinylfu_cache_insert_and_get() {
+    let mut cache: TinyLfuCache<String, String> = TinyLfuCache::new(100);
+
+    // 测试插入和获取
+    cache.insert("key1".to_string(), "value1".to_string());
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 测试不存在的键
+    assert_eq!(cache.get(&"nonexistent".to_string()), None);
+}
+
+#[test]
+fn test_tinylfu_cache_with_size_limit() {
+    let mut cache: TinyLfuCache<i32, i32> = TinyLfuCache::new(10);
+
+    for i in 0..100 {
+        cache.insert(i, i);
+        assert!(cache.len() <= 10);
+    }
+    assert_eq!(cache.len(), 10);
+}
+
+#[test]
+fn test_tinylfu_cache_keeps_frequent_entries() {
+    let capacity = 100;
+    let mut tinylfu: TinyLfuCache<i32, i32> = TinyLfuCache::new(capacity);
+    let mut lru: LruCache<i32, i32> = LruCache::new(capacity);
+    let hot: Vec<i32> = (0..50).collect();
+
+    // 热点数据反复访问
+    for _ in 0..5 {
+        for &key in &hot {
+            if tinylfu.get(&key).is_none() {
+                tinylfu.insert(key, key);
+            }
+            if lru.get(&key).is_none() {
+                lru.insert(key, key);
+            }
+        }
+    }
+
+    // 大量只出现一次的键
+    for key in 1000..3000 {
+        tinylfu.insert(key, key);
+        lru.insert(key, key);
+    }
+
+    let tinylfu_hits = hot.iter().filter(|key| tinylfu.contains_key(key)).count();
+    let lru_hits = hot.iter().filter(|key| lru.contains_key(key)).count();
+    // 频次估计有误差且会老化，允许少量热点被换出
+    assert!(tinylfu_hits >= hot.len() * 9 / 10, "tinylfu hits: {tinylfu_hits}");
+    assert_eq!(lru_hits, 0);
+}
+
+#[test]
+fn test_tinylfu_cache_with_ttl() {
+    let clock = MockClock::new();
+    let mut cache: TinyLfuCache<String, String> = TinyLfuCache::new(100).with_clock(clock.clone());
+
+    // 插入带TTL的值
+    cache.insert_with_ttl(
+        "key1".to_string(),
+        "value1".to_string(),
+        Some(Duration::from_millis(100)),
+    );
+
+    // 应该能获取到值
+    assert_eq!(cache.get(&"key1".to_string()), Some("value1".to_string()));
+
+    // 推进时钟，让条目过期
+    clock.advance(Duration::from_millis(150));
+
+    // 应该获取不到值（已过期）
+    assert_eq!(cache.get(&"key1".to_string()), None);
+}
+
+#[test]
+fn test_tinylfu_cache_remove_and_clear() {
+    let mut cache: TinyLfuCache<String, i32> = TinyLfuCache::new(10);
+
+    cache.insert("key1".to_string(), 1);
+    cache.insert("key2".to_string(), 2);
+    assert_eq!(cache.remove(&"key1".to_string()), Some(1));
+    assert_eq!(cache.get(&"key1".to_string()), None);
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
@@ -0,0 +1,119 @@
+use localcache::lib::basiccache::BasicCache;
+use localcache::lib::cache::{Cache, CacheError};
+use localcache::lib::lrucache::{LruCache, LruPolicy};
+use localcache::lib::policycache::PolicyCache;
+use localcache::lib::weigher::UnitWeigher;
+
+// 按值的字节数计算权重
+fn byte_weighted(max_weight: u64) -> PolicyCache<i32, String, LruPolicy<i32>> {
+    PolicyCache::unbounded(LruPolicy::new()).with_max_weight(max_weight, |_: &i32, value: &String| value.len() as u64)
+}
+
+// 测试淘汰会一直进行到总权重放得下新条目
+#[test]
+fn test_weight_evicts_until_fits() {
+    let mut cache = byte_weighted(10);
+    cache.insert(1, "aaa".to_string());
+    cache.insert(2, "bbb".to_string());
+    cache.insert(3, "ccc".to_string());
+    assert_eq!(cache.weight(), 9);
+
+    // 需要淘汰两个最久未使用的条目
+    cache.insert(4, "dddddd".to_string());
+    assert_eq!(cache.weight(), 9);
+    assert!(!cache.contains_key(&1));
+    assert!(!cache.contains_key(&2));
+    assert!(cache.contains_key(&3));
+    assert!(cache.contains_key(&4));
+}
+
+// 测试超过总容量的条目被拒绝，缓存保持不变
+#[test]
+fn test_entry_heavier_than_capacity_rejected() {
+    let mut cache = byte_weighted(10);
+    cache.insert(1, "aaa".to_string());
+    cache.insert(2, "bbb".to_string());
+
+    let result = cache.try_insert(3, "x".repeat(11));
+    assert_eq!(result, Err(CacheError::EntryTooHeavy { weight: 11, max_weight: 10 }));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.weight(), 6);
+
+    // insert 静默忽略，同样不会清空缓存
+    cache.insert(3, "x".repeat(11));
+    assert_eq!(cache.len(), 2);
+    assert!(!cache.contains_key(&3));
+
+    assert_eq!(cache.try_insert(3, "x".repeat(4)), Ok(()));
+    assert_eq!(cache.len(), 3);
+
+    // 覆盖已有的键被拒绝时旧值也被删除，不会再读到过时的值
+    cache.insert(1, "x".repeat(11));
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.weight(), 7);
+}
+
+// 测试覆盖写入时按新旧权重之差计算，变重时淘汰其他条目
+#[test]
+fn test_overwrite_adjusts_weight() {
+    let mut cache = byte_weighted(10);
+    cache.insert(1, "aaaa".to_string());
+    cache.insert(2, "bbbb".to_string());
+
+    cache.insert(1, "a".to_string());
+    assert_eq!(cache.weight(), 5);
+
+    cache.insert(1, "a".repeat(8));
+    assert_eq!(cache.weight(), 8);
+    assert!(!cache.contains_key(&2));
+    assert_eq!(cache.get(&1), Some("a".repeat(8)));
+}
+
+// 测试删除和清空会扣除权重
+#[test]
+fn test_remove_and_clear_release_weight() {
+    let mut cache = byte_weighted(100);
+    cache.insert(1, "aaaa".to_string());
+    cache.insert(2, "bb".to_string());
+    cache.remove(&1);
+    assert_eq!(cache.weight(), 2);
+    cache.clear();
+    assert_eq!(cache.weight(), 0);
+}
+
+// 测试条目数上限和权重上限同时生效
+#[test]
+fn test_weight_and_size_limits_together() {
+    let mut cache: LruCache<i32, i32> = LruCache::new(2).with_max_weight(100, UnitWeigher);
+    cache.insert(1, 1);
+    cache.insert(2, 2);
+    cache.insert(3, 3);
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.weight(), 2);
+
+    let mut cache: LruCache<i32, i32> = LruCache::new(100).with_max_weight(30, |_: &i32, v: &i32| *v as u64);
+    cache.insert(1, 10);
+    cache.insert(2, 10);
+    cache.insert(3, 10);
+    cache.insert(4, 10);
+    assert_eq!(cache.len(), 3);
+    assert!(!cache.contains_key(&1));
+}
+
+// 测试不淘汰的缓存超出权重上限时拒绝写入，而不是悄悄超出
+#[test]
+fn test_non_evicting_cache_rejects_over_weight() {
+    let mut cache = BasicCache::new().with_max_weight(10, |_: &i32, value: &String| value.len() as u64);
+    assert_eq!(cache.try_insert(1, "aaaa".to_string()), Ok(()));
+    assert_eq!(cache.try_insert(2, "bbbb".to_string()), Ok(()));
+    assert_eq!(cache.try_insert(3, "cccc".to_string()), Err(CacheError::CacheFull));
+    assert!(!cache.contains_key(&3));
+    assert_eq!(cache.weight(), 8);
+
+    // 覆盖写入后放不下的新值同样不会留在缓存中
+    assert_eq!(cache.try_insert(1, "a".repeat(7)), Err(CacheError::CacheFull));
+    assert!(!cache.contains_key(&1));
+    assert_eq!(cache.weight(), 4);
+    assert!(cache.weight() <= 10);
+}